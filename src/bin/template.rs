@@ -8,6 +8,7 @@ use serde_json::error::Category;
 use serde_json::Value;
 
 use template_cli::evaluate;
+use template_cli::evaluate::{Delimiters, Options, TemplateSource};
 
 #[derive(clap::ValueEnum, Clone, Eq, PartialEq)]
 enum ConfigurationFormat {
@@ -33,6 +34,15 @@ struct Cli {
     /// a non-standard extension, or when the input is given in the standard input stream.
     #[arg(short, long, value_enum)]
     format: Option<ConfigurationFormat>,
+
+    /// The start and end delimiters of template tags, separated by whitespace.
+    /// A delimiters directive in the template file takes precedence.
+    #[arg(short, long, default_value = "{% %}")]
+    delimiters: Delimiters,
+
+    /// Fail when the template references an undefined property.
+    #[arg(short, long)]
+    strict: bool,
 }
 
 
@@ -50,7 +60,12 @@ fn main() {
     let utf8_template_path = template_path.to_str().unwrap_or("<path not representable in UTF-8>");
     info!("Using template file '{}'", utf8_template_path);
 
-    let template_content = std::fs::read_to_string(template_path.clone())
+    let options = Options {
+        delimiters: args.delimiters,
+        strict: args.strict,
+    };
+    let template_source = std::fs::read_to_string(template_path.clone())
+        .map(|content| TemplateSource::new(&content, &options))
         .unwrap_or_else(|error| {
             error!("ERROR: Could not read template file '{}': {}", utf8_template_path, error);
             exit(ERR_TEMPLATE_FILE);
@@ -113,7 +128,7 @@ fn main() {
             })
     };
 
-    let file = evaluate::parse_template(&template_content)
+    let file = template_source.parse()
         .unwrap_or_else(|parse_error| {
             // Formatted content on new line
             error!("{}", format!("ERROR: Could not parse template\n{parse_error}"));
@@ -121,7 +136,7 @@ fn main() {
         })
         .next().unwrap();
 
-    let result = evaluate::evaluate_file(&configuration, file)
+    let result = evaluate::evaluate_file_with_options(&configuration, file, &template_path, &options)
         .unwrap_or_else(|template_render_error| {
             error!("ERROR: Could not render template: {}", template_render_error);
             exit(ERR_RENDERING_TEMPLATE)
@@ -1,9 +1,65 @@
 use std::fmt::{Debug, Display, Formatter};
 
+/// A value of the wrong type given to a function.
+#[derive(Debug)]
+pub struct ArgumentTypeError {
+    pub function: String,
+    /// The position of the value, where 0 is the value the function is applied to and 1 is the first argument.
+    pub position: usize,
+    pub expected: Vec<&'static str>,
+    pub actual: &'static str,
+    /// The value as JSON, truncated when it is long.
+    pub preview: String,
+}
+
+impl Display for ArgumentTypeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let position = match self.position {
+            0 => "the input".to_string(),
+            position => format!("argument {}", position),
+        };
+        write!(f, "Function '{}' expects {} to be of type {}, but got {}", self.function, position, self.expected.join(" or "), self.actual)?;
+        // The preview of null would repeat the type
+        if self.actual != "null" {
+            write!(f, " {}", self.preview)?;
+        }
+        Ok(())
+    }
+}
+
+/// The location of the part of a template that failed to render.
+#[derive(Debug)]
+pub struct TemplateLocation {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    /// The line of the template containing the location.
+    pub source_line: String,
+    /// The number of characters of the failing part on the source line.
+    pub length: usize,
+}
+
+impl Display for TemplateLocation {
+    /// Formats the location as an excerpt of the template, with the failing part annotated.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let line_number = self.line.to_string();
+        let padding = " ".repeat(line_number.len());
+        let marker = match self.length {
+            0 | 1 => "^".to_string(),
+            length => format!("^{}^", "-".repeat(length - 2)),
+        };
+        writeln!(f, "{}--> {}:{}:{}", padding, self.path, self.line, self.column)?;
+        writeln!(f, "{} |", padding)?;
+        writeln!(f, "{} | {}", line_number, self.source_line)?;
+        write!(f, "{} | {}{}", padding, " ".repeat(self.column - 1), marker)
+    }
+}
+
 #[derive(Debug)]
 pub enum TemplateRenderError {
     UnknownFunctionError(String),
     TypeError(String),
+    ArgumentTypeError(ArgumentTypeError),
     ArgumentValueError(String),
     LiteralParseError(String),
     RequiredArgumentMissing(String),
@@ -11,6 +67,15 @@ pub enum TemplateRenderError {
     JsonParseError(String),
     JsonSerializationError,
     AssertionError(String),
+    ArithmeticError(String),
+    IncludeError(String),
+    IncludedTemplateError(String, Box<TemplateRenderError>),
+    ExtendError(String),
+    ExtendedTemplateError(String, Box<TemplateRenderError>),
+    MacroRecursionError(String),
+    IterationError(String),
+    UndefinedPropertyError(String),
+    LocatedError(Box<TemplateRenderError>, TemplateLocation),
 }
 
 impl Display for TemplateRenderError {
@@ -18,6 +83,7 @@ impl Display for TemplateRenderError {
         match self {
             TemplateRenderError::UnknownFunctionError(string) => f.write_str(format!("Unknown function '{}'", string.as_str()).as_str())?,
             TemplateRenderError::TypeError(string) => f.write_str(format!("Invalid type '{}'", string.as_str()).as_str())?,
+            TemplateRenderError::ArgumentTypeError(error) => f.write_str(format!("Invalid type: {}", error).as_str())?,
             TemplateRenderError::ArgumentValueError(string) => f.write_str(format!("Invalid arguments: {}", string.as_str()).as_str())?,
             TemplateRenderError::LiteralParseError(string) => f.write_str(format!("Could not parse literal '{}'", string.as_str()).as_str())?,
             TemplateRenderError::RequiredArgumentMissing(string) => f.write_str(format!("Required argument is missing for function {}", string.as_str()).as_str())?,
@@ -25,6 +91,15 @@ impl Display for TemplateRenderError {
             TemplateRenderError::JsonParseError(json) => f.write_str(format!("Could not parse JSON: '{}'", json.as_str()).as_str())?,
             TemplateRenderError::JsonSerializationError => f.write_str(format!("Could not serialize JSON").as_str())?,
             TemplateRenderError::AssertionError(message) => f.write_str(format!("Assertion failed: {}", message.as_str()).as_str())?,
+            TemplateRenderError::ArithmeticError(message) => f.write_str(format!("Arithmetic error: {}", message.as_str()).as_str())?,
+            TemplateRenderError::IncludeError(message) => f.write_str(format!("Could not include template: {}", message.as_str()).as_str())?,
+            TemplateRenderError::IncludedTemplateError(path, error) => f.write_str(format!("{}\nin included template '{}'", error, path.as_str()).as_str())?,
+            TemplateRenderError::ExtendError(message) => f.write_str(format!("Could not extend template: {}", message.as_str()).as_str())?,
+            TemplateRenderError::ExtendedTemplateError(path, error) => f.write_str(format!("{}\nin extended template '{}'", error, path.as_str()).as_str())?,
+            TemplateRenderError::MacroRecursionError(name) => f.write_str(format!("Maximum recursion depth exceeded when calling macro '{}'", name.as_str()).as_str())?,
+            TemplateRenderError::IterationError(message) => f.write_str(format!("Could not iterate: {}", message.as_str()).as_str())?,
+            TemplateRenderError::UndefinedPropertyError(path) => f.write_str(format!("Undefined property '{}'", path.as_str()).as_str())?,
+            TemplateRenderError::LocatedError(error, location) => f.write_str(format!("{}\n{}", error, location).as_str())?,
         }
         return Ok(());
     }
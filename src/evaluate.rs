@@ -1,30 +1,199 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::str::{Chars, FromStr};
 
+use itertools::Itertools;
 use log::info;
+use pest::error::InputLocation;
 use pest::iterators::{Pair, Pairs};
-use pest::Parser;
+use pest::{Parser, Position};
 use serde_json::{json, Map, Value};
 
-use crate::error::TemplateRenderError;
+use crate::error::{TemplateLocation, TemplateRenderError};
 use crate::function;
+use crate::operator;
 
 #[derive(Parser)]
 #[grammar = "grammar/template.pest"]
 pub struct TemplateParser;
 
-fn format_string(value: &Value) -> String {
-    match value {
-        Value::Null => "".to_string(),
-        Value::Bool(boolean) => boolean.to_string(),
-        Value::Number(number) => number.to_string(),
-        Value::String(string) => string.to_string(),
-        Value::Array(values) => format!("[{}]", values.iter().map(|v| format_string(v)).reduce(|cur, next| format!("{},{}", &cur, &next)).unwrap_or("".to_string())),
-        Value::Object(object) => format!("{{{}}}", object.iter().map(|(k, v)| format!("{}:{}", k, format_string(v))).reduce(|cur, next| format!("{},{}", &cur, &next)).unwrap_or("".to_string())),
+/// The delimiters that start and end a template tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delimiters {
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for Delimiters {
+    fn default() -> Self {
+        Delimiters {
+            start: "{%".to_string(),
+            end: "%}".to_string(),
+        }
     }
 }
 
-fn parse_literal(value: &Value, literal: &Pair<Rule>) -> Result<Value, TemplateRenderError> {
+impl FromStr for Delimiters {
+    type Err = String;
+
+    /// Parses delimiters separated by whitespace, like `<% %>`.
+    fn from_str(delimiters: &str) -> Result<Self, Self::Err> {
+        match delimiters.split_whitespace().collect::<Vec<_>>()[..] {
+            [start, end] if !start.contains("#}") && !end.contains("#}") => Ok(Delimiters {
+                start: start.to_string(),
+                end: end.to_string(),
+            }),
+            _ => Err(format!("Invalid delimiters '{}', expected a start and end delimiter separated by whitespace, like '<% %>'", delimiters)),
+        }
+    }
+}
+
+impl Delimiters {
+    /// The delimiters directive that selects these delimiters, which is empty for the default delimiters.
+    fn directive(&self) -> String {
+        if *self == Delimiters::default() {
+            String::new()
+        } else {
+            format!("{{# delimiters {} {} #}}", self.start, self.end)
+        }
+    }
+}
+
+/// Options for rendering a template, which also apply to included and extended templates.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    pub delimiters: Delimiters,
+    /// Fail rendering when the template references an undefined property, or when the condition of an `if` block fails to evaluate.
+    pub strict: bool,
+}
+
+/// The content of a template, prepared to be parsed with the delimiters of the options.
+pub struct TemplateSource {
+    /// The template content, prefixed with a delimiters directive when the delimiters are not the default ones.
+    /// A delimiters directive in the template content itself takes precedence.
+    content: String,
+    delimiters: Delimiters,
+}
+
+impl TemplateSource {
+    pub fn new(template_content: &str, options: &Options) -> TemplateSource {
+        TemplateSource {
+            content: format!("{}{}", options.delimiters.directive(), template_content),
+            delimiters: options.delimiters.clone(),
+        }
+    }
+
+    /// Parses the template. The location of a parse error refers to the template content as it was given.
+    pub fn parse(&self) -> Result<Pairs<'_, Rule>, Box<pest::error::Error<Rule>>> {
+        TemplateParser::parse(Rule::file, &self.content)
+            .map_err(|error| {
+                let position = |offset: usize| content_position(&self.content, offset, &self.delimiters);
+                Box::new(match error.location {
+                    InputLocation::Pos(offset) => pest::error::Error::new_from_pos(error.variant, position(offset)),
+                    InputLocation::Span((start, end)) => pest::error::Error::new_from_span(error.variant, position(start).span(&position(end))),
+                })
+            })
+    }
+}
+
+/// The position in the template content as it was given, leaving out the delimiters directive added by `TemplateSource`.
+fn content_position<'i>(input: &'i str, offset: usize, delimiters: &Delimiters) -> Position<'i> {
+    let directive = delimiters.directive();
+    let directive_length = if input.starts_with(directive.as_str()) { directive.len() } else { 0 };
+    Position::new(&input[directive_length..], offset.saturating_sub(directive_length)).unwrap()
+}
+
+#[derive(Clone)]
+struct Context<'a> {
+    /// The paths of the template files being evaluated, starting with the root template and ending with the innermost included or extended template.
+    template_paths: Vec<PathBuf>,
+    /// The named blocks of each template in an inheritance chain, starting with the most derived template.
+    blocks: Vec<HashMap<String, Definition<'a>>>,
+    /// The overridden definitions of the block that is currently evaluated, rendered by `super`.
+    parent_blocks: Vec<Definition<'a>>,
+    /// The macros that can be called, by name.
+    macros: HashMap<String, Definition<'a>>,
+    /// The number of nested macro calls.
+    macro_depth: usize,
+    options: Options,
+}
+
+/// A block or macro, with the path of the template file that defines it.
+#[derive(Clone)]
+struct Definition<'a> {
+    template_path: PathBuf,
+    pair: Pair<'a, Rule>,
+}
+
+impl<'a> Definition<'a> {
+    /// The context to evaluate the definition in, which locates errors and resolves templates relative to the template
+    /// file that defines it.
+    fn context(&self, context: &Context<'a>) -> Context<'a> {
+        let mut definition_context = context.clone();
+        if context.template_paths.last() != Some(&self.template_path) {
+            definition_context.template_paths.push(self.template_path.clone());
+        }
+        definition_context
+    }
+}
+
+const MAXIMUM_MACRO_DEPTH: usize = 64;
+
+fn parse_unicode_escape(characters: &mut Chars) -> Option<u32> {
+    let digits: String = characters.take(4).collect();
+    if digits.len() != 4 || !digits.chars().all(|digit| digit.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+/// Replaces the escape sequences in the content of a string literal. The escape sequences of JSON strings are supported,
+/// including surrogate pairs of UTF-16 code units, and `\'` for single-quoted strings.
+/// Returns `None` when the content contains an invalid escape sequence.
+fn unescape_string(content: &str) -> Option<String> {
+    let mut result = String::new();
+    let mut characters = content.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            result.push(character);
+            continue;
+        }
+        let unescaped = match characters.next()? {
+            '"' => '"',
+            '\'' => '\'',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let code_unit = parse_unicode_escape(&mut characters)?;
+                if (0xD800..0xDC00).contains(&code_unit) {
+                    if characters.next()? != '\\' || characters.next()? != 'u' {
+                        return None;
+                    }
+                    let low_code_unit = parse_unicode_escape(&mut characters)?;
+                    if !(0xDC00..0xE000).contains(&low_code_unit) {
+                        return None;
+                    }
+                    char::from_u32(0x10000 + ((code_unit - 0xD800) << 10) + (low_code_unit - 0xDC00))?
+                } else {
+                    char::from_u32(code_unit)?
+                }
+            }
+            _ => return None,
+        };
+        result.push(unescaped);
+    }
+    Some(result)
+}
+
+fn parse_literal(context: &Context, value: &Value, literal: &Pair<Rule>) -> Result<Value, TemplateRenderError> {
     let content = literal.as_str().to_string();
     match literal.as_rule() {
         Rule::null => Ok(Value::Null),
@@ -40,13 +209,13 @@ fn parse_literal(value: &Value, literal: &Pair<Rule>) -> Result<Value, TemplateR
                 .map(|result| Value::from(result)),
             _ => unreachable!()
         }
-        Rule::string => content.parse::<String>()
-            .map_err(|_err| TemplateRenderError::LiteralParseError(content))
-            .map(|result| Value::from(&result[1..result.len() - 1])),
+        Rule::string => unescape_string(&content[1..content.len() - 1])
+            .ok_or(TemplateRenderError::LiteralParseError(content))
+            .map(Value::String),
         Rule::array => {
             let array: Result<Vec<Value>, TemplateRenderError> = literal.clone().into_inner()
                 .into_iter()
-                .map(|inner_literal| parse_expression(&value, &mut inner_literal.into_inner()))
+                .map(|inner_literal| parse_expression(context, &value, &mut inner_literal.into_inner()))
                 .collect();
 
             array.map(|result| Value::Array(result))
@@ -55,11 +224,19 @@ fn parse_literal(value: &Value, literal: &Pair<Rule>) -> Result<Value, TemplateR
             let mut result = Map::new();
             for pair in literal.clone().into_inner() {
                 let mut key_value_content = pair.into_inner();
-                let pair_key = key_value_content.next().unwrap().as_str();
+                let pair_key = key_value_content.next().unwrap();
+                let pair_key = match pair_key.as_rule() {
+                    Rule::string => {
+                        let content = pair_key.as_str();
+                        unescape_string(&content[1..content.len() - 1])
+                            .ok_or_else(|| TemplateRenderError::LiteralParseError(content.to_string()))?
+                    }
+                    _ => pair_key.as_str().to_string(),
+                };
                 let pair_value = key_value_content.next().unwrap();
-                let evaluated_pair_value = parse_expression(&value, &mut pair_value.into_inner())?;
+                let evaluated_pair_value = parse_expression(context, &value, &mut pair_value.into_inner())?;
 
-                result.insert(pair_key.to_string(), evaluated_pair_value);
+                result.insert(pair_key, evaluated_pair_value);
             }
 
             Ok(Value::Object(result))
@@ -68,45 +245,189 @@ fn parse_literal(value: &Value, literal: &Pair<Rule>) -> Result<Value, TemplateR
     }
 }
 
-fn parse_expression(value: &Value, expression: &mut Pairs<Rule>) -> Result<Value, TemplateRenderError> {
-    let properties_or_literal = expression.next().unwrap();
+fn parse_expression(context: &Context, value: &Value, expression: &mut Pairs<Rule>) -> Result<Value, TemplateRenderError> {
+    let operation = expression.next().unwrap();
+    parse_operation(context, value, operation.clone())
+        .map_err(|error| locate_error(context, &operation, error))
+}
+
+fn parse_operation(context: &Context, value: &Value, operation: Pair<Rule>) -> Result<Value, TemplateRenderError> {
+    match operation.as_rule() {
+        Rule::conditional => {
+            let mut operands = operation.into_inner();
+            let condition = parse_operation(context, value, operands.next().unwrap())?;
+            match (operands.next(), operands.next()) {
+                (Some(when_true), Some(when_false)) => {
+                    // Only the selected branch is evaluated
+                    let selected = if function::to_boolean(&condition) { when_true } else { when_false };
+                    parse_operation(context, value, selected)
+                }
+                _ => Ok(condition),
+            }
+        }
+        Rule::disjunction | Rule::conjunction => {
+            // Short-circuit: a disjunction stops at the first truthy operand, a conjunction at the first falsy operand
+            let short_circuit = operation.as_rule() == Rule::disjunction;
+            let mut operands = operation.into_inner();
+            let result = parse_operation(context, value, operands.next().unwrap())?;
+            if operands.peek().is_none() {
+                return Ok(result);
+            }
+            let mut boolean_result = function::to_boolean(&result);
+            while boolean_result != short_circuit {
+                match operands.nth(1) {
+                    Some(operand) => boolean_result = function::to_boolean(&parse_operation(context, value, operand)?),
+                    None => break,
+                }
+            }
+            Ok(Value::Bool(boolean_result))
+        }
+        Rule::negation => {
+            let mut negations = 0;
+            let mut result = Value::Null;
+            for negation in operation.into_inner() {
+                match negation.as_rule() {
+                    Rule::negation_operator => negations += 1,
+                    _ => result = parse_operation(context, value, negation)?,
+                }
+            }
+            if negations == 0 {
+                Ok(result)
+            } else {
+                Ok(Value::Bool(function::to_boolean(&result) ^ (negations % 2 == 1)))
+            }
+        }
+        Rule::comparison | Rule::sum | Rule::product => {
+            let mut operands = operation.into_inner();
+            let mut result = parse_operation(context, value, operands.next().unwrap())?;
+            while let Some(operator) = operands.next() {
+                let right = parse_operation(context, value, operands.next().unwrap())?;
+                result = operator::apply_binary_operator(&result, operator.as_str(), &right)?;
+            }
+            Ok(result)
+        }
+        Rule::pipeline => parse_pipeline(context, value, &mut operation.into_inner()),
+        Rule::expression => parse_expression(context, value, &mut operation.into_inner()),
+        _ => unreachable!()
+    }
+}
+
+/// Looks up a dictionary key or an array index. Negative indexes count from the end of the array.
+/// A missing key or index, or a lookup in a value that is not a dictionary or array, results in `None`.
+fn index_value(value: &Value, index: &Value) -> Result<Option<Value>, TemplateRenderError> {
+    match index {
+        Value::String(key) => Ok(value.get(key).cloned()),
+        Value::Number(number) if number.is_i64() => {
+            let integer = number.as_i64().unwrap();
+            let item = value.as_array().and_then(|array| {
+                let position = if integer < 0 { array.len().checked_sub(integer.unsigned_abs() as usize)? } else { integer as usize };
+                array.get(position)
+            });
+            Ok(item.cloned())
+        }
+        _ => Err(TemplateRenderError::ArgumentValueError(format!("Index must be a string or an integer, but was {}", index))),
+    }
+}
+
+/// The location of a parsed pair in the template file that is currently evaluated.
+fn template_location(context: &Context, pair: &Pair<Rule>) -> TemplateLocation {
+    let span = pair.as_span();
+    // Block tags start with the whitespace before the tag
+    let content = span.as_str().trim_start();
+    let start = content_position(span.get_input(), span.end() - content.len(), &context.options.delimiters);
+    let (line, column) = start.line_col();
+    let source_line = start.line_of().trim_end_matches(['\r', '\n']);
+    TemplateLocation {
+        path: context.template_paths.last().unwrap().display().to_string(),
+        line,
+        column,
+        source_line: source_line.to_string(),
+        length: content.lines().next().unwrap_or("").chars().count(),
+    }
+}
+
+/// Adds the location of the pair to the error, unless the error already has a more specific location.
+fn locate_error(context: &Context, pair: &Pair<Rule>, error: TemplateRenderError) -> TemplateRenderError {
+    match error {
+        TemplateRenderError::LocatedError(_, _) => error,
+        _ => TemplateRenderError::LocatedError(Box::new(error), template_location(context, pair)),
+    }
+}
+
+/// An undefined property results in `null`, or fails in strict mode unless the property is optional.
+fn defined_or_null(context: &Context, defined_value: Option<Value>, properties: &Pair<Rule>, optional: bool) -> Result<Value, TemplateRenderError> {
+    match defined_value {
+        Some(defined_value) => Ok(defined_value),
+        None if context.options.strict && !optional => Err(locate_error(context, properties, TemplateRenderError::UndefinedPropertyError(properties.as_str().to_string()))),
+        None => Ok(Value::Null),
+    }
+}
+
+fn parse_pipeline(context: &Context, value: &Value, pipeline: &mut Pairs<Rule>) -> Result<Value, TemplateRenderError> {
+    let properties_or_literal = pipeline.next().unwrap();
 
     let current_value = match properties_or_literal.as_rule() {
         Rule::literal => {
-            parse_literal(&value, &properties_or_literal.into_inner().next().unwrap())?
+            parse_literal(context, &value, &properties_or_literal.into_inner().next().unwrap())?
         }
         Rule::properties => {
+            // A property piped into `default` or `coalesce` is optional
+            let optional = pipeline.peek()
+                .map(|function| function.into_inner().next().unwrap().as_str())
+                .is_some_and(|function_name| ["default", "coalesce"].contains(&function_name) && !context.macros.contains_key(function_name));
             let mut current_value = value.clone();
-            for property in properties_or_literal.into_inner() {
+            for property in properties_or_literal.clone().into_inner() {
                 match property.as_rule() {
                     Rule::property => {
-                        current_value = current_value[property.as_str()].clone();
+                        let defined_value = current_value.get(property.as_str()).cloned();
+                        current_value = defined_or_null(context, defined_value, &properties_or_literal, optional)?;
+                    }
+                    Rule::property_index => {
+                        let index = parse_expression(context, value, &mut property.into_inner().next().unwrap().into_inner())?;
+                        let defined_value = index_value(&current_value, &index)?;
+                        current_value = defined_or_null(context, defined_value, &properties_or_literal, optional)?;
                     }
                     _ => unreachable!(),
                 }
             }
             current_value
         }
+        Rule::call => {
+            let mut function_and_arguments = properties_or_literal.into_inner();
+            let function_name = function_and_arguments.next().unwrap().as_str();
+            let mut arguments: Vec<Value> = vec![];
+            for argument in function_and_arguments {
+                arguments.push(parse_expression(context, value, &mut argument.into_inner())?)
+            }
+
+            call_function(context, function_name, &arguments)?
+        }
+        Rule::expression => parse_expression(context, value, &mut properties_or_literal.into_inner())?,
         _ => unreachable!()
     };
 
     let mut result = current_value;
-    for function in expression {
+    for function in pipeline {
         match function.as_rule() {
             Rule::function_call => {
                 let mut function_and_arguments = function.into_inner();
                 let function_name = function_and_arguments.next().unwrap().as_str();
-                let mut arguments: Vec<Value> = vec![];
+                let mut arguments: Vec<Value> = vec![result];
+                let mut lambda = None;
                 for argument in function_and_arguments {
                     match argument.as_rule() {
                         Rule::expression => {
-                            arguments.push(parse_expression(value, &mut argument.into_inner())?)
+                            arguments.push(parse_expression(context, value, &mut argument.into_inner())?)
                         }
+                        Rule::lambda => lambda = Some(argument),
                         _ => unreachable!(),
                     }
                 }
 
-                result = function::apply_function(&result, function_name, &arguments)?;
+                result = match lambda {
+                    Some(lambda) => apply_lambda_function(context, value, function_name, &arguments[0], lambda)?,
+                    None => call_function(context, function_name, &arguments)?,
+                };
             }
             _ => unreachable!(),
         }
@@ -114,8 +435,130 @@ fn parse_expression(value: &Value, expression: &mut Pairs<Rule>) -> Result<Value
     return Ok(result);
 }
 
+/// Evaluates the body of the lambda with its parameter bound to the argument, in a child scope of the data.
+fn call_lambda(context: &Context, data: &Value, lambda: &Pair<Rule>, argument: &Value) -> Result<Value, TemplateRenderError> {
+    let mut parameter_and_body = lambda.clone().into_inner();
+    let parameter = parameter_and_body.next().unwrap().as_str();
+    let mut scope = match data {
+        Value::Object(map) => map.clone(),
+        _ => Map::new(),
+    };
+    scope.insert(parameter.to_string(), argument.clone());
+    parse_expression(context, &Value::Object(scope), &mut parameter_and_body.next().unwrap().into_inner())
+}
+
+/// Applies a higher-order function, which calls the lambda for each item of the array value, or for each value or key
+/// of the dictionary value.
+fn apply_lambda_function(context: &Context, data: &Value, function_name: &str, value: &Value, lambda: Pair<Rule>) -> Result<Value, TemplateRenderError> {
+    match function_name {
+        "map" | "filter" | "select" | "all" | "any" | "none" | "some" => (),
+        "mapValues" | "mapKeys" => {
+            let object = function::require_object_value(function_name, value)?;
+            let mut result = Map::new();
+            for (key, item) in object {
+                if function_name == "mapValues" {
+                    result.insert(key.clone(), call_lambda(context, data, &lambda, item)?);
+                } else {
+                    let mapped_key = call_lambda(context, data, &lambda, &Value::String(key.clone()))?;
+                    result.insert(function::format_string(&mapped_key), item.clone());
+                }
+            }
+            return Ok(Value::Object(result));
+        }
+        _ => return Err(TemplateRenderError::ArgumentValueError(format!("Function '{}' does not accept a lambda argument", function_name))),
+    }
+
+    let items = function::require_array_value(function_name, value)?;
+    let mut results = vec![];
+    for item in items {
+        results.push(call_lambda(context, data, &lambda, item)?);
+    }
+
+    match function_name {
+        "map" => Ok(Value::Array(results)),
+        "filter" | "select" => Ok(Value::Array(items.iter().zip(results)
+            .filter(|(_, result)| function::to_boolean(result))
+            .map(|(item, _)| item.clone())
+            .collect())),
+        // The predicate functions test the results of the lambda
+        _ => function::apply_function(&Value::Array(results), function_name, &vec![]),
+    }
+}
+
+/// Calls a macro or a function. The first argument is the value that the function is applied to.
+fn call_function(context: &Context, function_name: &str, arguments: &[Value]) -> Result<Value, TemplateRenderError> {
+    if let Some(definition) = context.macros.get(function_name) {
+        return call_macro(context, function_name, definition.clone(), arguments);
+    }
+
+    match arguments.split_first() {
+        Some((value, function_arguments)) => function::apply_function(value, function_name, &function_arguments.to_vec()),
+        None => function::apply_function(&Value::Null, function_name, &vec![]),
+    }
+}
+
+fn call_macro<'a>(context: &Context<'a>, macro_name: &str, definition: Definition<'a>, arguments: &[Value]) -> Result<Value, TemplateRenderError> {
+    if context.macro_depth >= MAXIMUM_MACRO_DEPTH {
+        return Err(TemplateRenderError::MacroRecursionError(macro_name.to_string()));
+    }
+
+    let parameters: Vec<&str> = definition.pair.clone().into_inner()
+        .filter(|pair| pair.as_rule() == Rule::property)
+        .map(|pair| pair.as_str())
+        .collect();
+    if arguments.len() > parameters.len() {
+        return Err(TemplateRenderError::ArgumentValueError(format!("Macro '{}' takes {} arguments but {} were given", macro_name, parameters.len(), arguments.len())));
+    }
 
-fn evaluate_template(data: &Value, record: Pair<Rule>) -> Result<String, TemplateRenderError> {
+    // The macro body only sees its own arguments, missing arguments are null
+    let mut scope = Map::new();
+    for (index, parameter) in parameters.iter().enumerate() {
+        scope.insert(parameter.to_string(), arguments.get(index).cloned().unwrap_or(Value::Null));
+    }
+
+    let mut macro_context = definition.context(context);
+    macro_context.macro_depth += 1;
+    evaluate_block(&macro_context, &Value::Object(scope), definition.pair)
+        .map(Value::String)
+}
+
+/// Trims the content before an intermediate or closing block tag, according to the whitespace control marker of the tag.
+/// By default horizontal whitespace is removed, `{%-` removes all whitespace and `{%+` keeps all whitespace.
+fn trim_before_tag(content: &str, tag: &Pair<Rule>) -> String {
+    match tag.clone().into_inner().next().map(|pair| pair.as_rule()) {
+        Some(Rule::trim_whitespace) => content.trim_end().to_string(),
+        Some(Rule::keep_whitespace) => content.to_string(),
+        _ => content.trim_end_matches([' ', '\t']).to_string(),
+    }
+}
+
+/// Trims the content of each loop iteration before an intermediate or closing block tag.
+/// Loop iterations keep their whitespace, unless the tag starts with `{%-`.
+fn trim_iterations_before_tag(iterables_results: &[Rc<RefCell<String>>], tag: &Pair<Rule>) {
+    if tag.clone().into_inner().next().map(|pair| pair.as_rule()) != Some(Rule::trim_whitespace) {
+        return;
+    }
+    for iterable_result in iterables_results {
+        let trimmed = trim_before_tag(iterable_result.borrow().as_str(), tag);
+        iterable_result.replace(trimmed);
+    }
+}
+
+/// Evaluates the truthiness of a condition. A condition that fails to evaluate is falsy, unless in strict mode.
+fn evaluate_condition(context: &Context, data: &Value, condition: Pair<Rule>) -> Result<bool, TemplateRenderError> {
+    match parse_expression(context, data, &mut condition.into_inner()) {
+        Ok(value) => Ok(function::to_boolean(&value)),
+        Err(error) if context.options.strict => Err(error),
+        Err(_) => Ok(false),
+    }
+}
+
+fn evaluate_template<'a>(context: &Context<'a>, data: &Value, record: Pair<'a, Rule>) -> Result<String, TemplateRenderError> {
+    evaluate_template_content(context, data, record.clone())
+        .map_err(|error| locate_error(context, &record, error))
+}
+
+fn evaluate_template_content<'a>(context: &Context<'a>, data: &Value, record: Pair<'a, Rule>) -> Result<String, TemplateRenderError> {
     let mut result = String::new();
 
     let mut inner_rules = record.into_inner();
@@ -138,34 +581,28 @@ fn evaluate_template(data: &Value, record: Pair<Rule>) -> Result<String, Templat
                             _ => unreachable!(),
                         };
                         let if_expression = if_inner_expression.next().unwrap();
-                        let if_result = parse_expression(&data, &mut if_expression.into_inner())
-                            .map(|value| function::to_boolean(&value))
-                            .unwrap_or(false);
+                        let if_result = evaluate_condition(context, data, if_expression)?;
                         if if_result ^ invert {
                             done = true;
                             valid = true
                         }
                     }
                     Rule::elif_template => {
-                        result = result.trim_end_matches(&[' ', '\t']).to_string();
+                        result = trim_before_tag(&result, &if_inner);
                         valid = false;
 
-                        let mut elif_inner_expression = if_inner.into_inner();
-                        let elif_expression = elif_inner_expression.next().unwrap();
-                        let elif_result = parse_expression(&data, &mut elif_expression.into_inner())
-                            .map(|value| function::to_boolean(&value))
-                            .unwrap_or(false);
-                        if !done && elif_result {
+                        let elif_expression = if_inner.into_inner().find(|pair| pair.as_rule() == Rule::expression).unwrap();
+                        if !done && evaluate_condition(context, data, elif_expression)? {
                             done = true;
                             valid = true
                         }
                     }
                     Rule::else_template => {
-                        result = result.trim_end_matches(&[' ', '\t']).to_string();
+                        result = trim_before_tag(&result, &if_inner);
                         valid = !done;
                     }
                     Rule::end_template => {
-                        result = result.trim_end_matches(&[' ', '\t']).to_string();
+                        result = trim_before_tag(&result, &if_inner);
                         valid = false;
                         done = true;
                     }
@@ -176,7 +613,7 @@ fn evaluate_template(data: &Value, record: Pair<Rule>) -> Result<String, Templat
                     }
                     Rule::template => {
                         if valid {
-                            let evaluation = evaluate_template(&data, if_inner)?;
+                            let evaluation = evaluate_template(context, &data, if_inner)?;
                             result.push_str(evaluation.as_str())
                         }
                     }
@@ -185,43 +622,59 @@ fn evaluate_template(data: &Value, record: Pair<Rule>) -> Result<String, Templat
             }
         }
         Rule::for_else_template => {
-            let mut valid = false;
-            let mut done = false;
-            let mut iterable_name: &str = "";
+            // The content after `else` is rendered once when there is nothing to iterate
+            let mut in_else = false;
+            let mut iterable_names: Vec<&str> = vec![];
             let mut iterables: Vec<Value> = vec![];
             let mut iterables_results: Vec<Rc<RefCell<String>>> = vec![];
+            let mut iterable_expression = None;
 
             for for_inner in expression.into_inner() {
                 match for_inner.as_rule() {
                     Rule::for_template => {
                         let mut for_inner_expression = for_inner.into_inner();
-                        iterable_name = for_inner_expression.next().unwrap().as_str();
-                        let for_iterable = parse_expression(&data, &mut for_inner_expression.next().unwrap().into_inner()).unwrap();
+                        while for_inner_expression.peek().unwrap().as_rule() == Rule::property {
+                            iterable_names.push(for_inner_expression.next().unwrap().as_str());
+                        }
+                        let for_iterable_expression = for_inner_expression.next().unwrap();
+                        let for_iterable = parse_expression(context, &data, &mut for_iterable_expression.clone().into_inner())?;
                         iterables = match for_iterable {
+                            Value::Null => vec![],
                             Value::Array(items) => items,
-                            _ => vec![],
+                            // Dictionaries are iterated as [key, value] pairs, in document order
+                            Value::Object(object) => object.into_iter()
+                                .map(|(key, value)| Value::Array(vec![Value::String(key), value]))
+                                .collect(),
+                            _ => return Err(locate_error(context, &for_iterable_expression, TemplateRenderError::IterationError(
+                                format!("Expected an array, dictionary or null, but got {}", function::describe(&for_iterable))))),
                         };
-                        done = iterables.is_empty();
-                        valid = !iterables.is_empty();
+                        iterable_expression = Some(for_iterable_expression);
                         iterables_results = iterables.iter().map(|_| Rc::new(RefCell::new(String::new()))).collect();
                     }
                     Rule::else_template => {
-                        result = result.trim_end_matches(&[' ', '\t']).to_string();
-                        valid = false;
-                        if !done {
-                            valid = true
-                        }
+                        trim_iterations_before_tag(&iterables_results, &for_inner);
+                        in_else = true;
                     }
                     Rule::end_template => {
-                        result = result.trim_end_matches(&[' ', '\t']).to_string();
-                        valid = false;
-                        done = true;
+                        if in_else {
+                            result = trim_before_tag(&result, &for_inner);
+                        } else {
+                            trim_iterations_before_tag(&iterables_results, &for_inner);
+                        }
                     }
                     Rule::character => {
-                        if valid {
+                        if !in_else {
                             iterables_results.iter_mut().for_each(|iterables_result| {
                                 iterables_result.replace_with(|r| format!("{}{}", r, for_inner.as_str()));
                             })
+                        } else if iterables.is_empty() {
+                            result.push_str(for_inner.as_str())
+                        }
+                    }
+                    Rule::template if in_else => {
+                        if iterables.is_empty() {
+                            let template_result = evaluate_template(context, data, for_inner)?;
+                            result.push_str(template_result.as_str());
                         }
                     }
                     Rule::template => {
@@ -230,7 +683,16 @@ fn evaluate_template(data: &Value, record: Pair<Rule>) -> Result<String, Templat
                             let context_value = match data {
                                 Value::Object(map) => {
                                     let mut q = map.clone();
-                                    q.insert(iterable_name.to_string(), iterable.clone());
+                                    if let [iterable_name] = iterable_names[..] {
+                                        q.insert(iterable_name.to_string(), iterable.clone());
+                                    } else {
+                                        let items = iterable.as_array()
+                                            .ok_or_else(|| locate_error(context, iterable_expression.as_ref().unwrap(), TemplateRenderError::IterationError(
+                                                format!("Expected an array to destructure into {}, but got {}", iterable_names.join(", "), function::describe(iterable)))))?;
+                                        for (item_index, iterable_name) in iterable_names.iter().enumerate() {
+                                            q.insert(iterable_name.to_string(), items.get(item_index).cloned().unwrap_or(Value::Null));
+                                        }
+                                    }
                                     let first = index == 0;
                                     let last = index == iterables.len() - 1;
                                     let index0 = index;
@@ -247,7 +709,7 @@ fn evaluate_template(data: &Value, record: Pair<Rule>) -> Result<String, Templat
                                 }
                                 _ => iterable.clone(),
                             };
-                            let template_result = evaluate_template(&context_value, for_inner.clone())?;
+                            let template_result = evaluate_template(context, &context_value, for_inner.clone())?;
                             iterable_result.replace_with(|current_result|
                                 format!("{}{}", current_result, template_result)
                             );
@@ -274,10 +736,10 @@ fn evaluate_template(data: &Value, record: Pair<Rule>) -> Result<String, Templat
                         name = for_inner.as_str();
                     }
                     Rule::expression => {
-                        value = parse_expression(&data, &mut for_inner.into_inner())?;
+                        value = parse_expression(context, &data, &mut for_inner.into_inner())?;
                     }
                     Rule::end_template => {
-                        result = result.trim_end_matches(&[' ', '\t']).to_string();
+                        result = trim_before_tag(&result, &for_inner);
                     }
                     Rule::character => {
                         result.push_str(for_inner.as_str())
@@ -291,7 +753,7 @@ fn evaluate_template(data: &Value, record: Pair<Rule>) -> Result<String, Templat
                             }
                             _ => value.clone(),
                         };
-                        let template_result = evaluate_template(&context_value, for_inner.clone())?;
+                        let template_result = evaluate_template(context, &context_value, for_inner.clone())?;
                         result.push_str(template_result.as_str());
                     }
                     _ => unreachable!(),
@@ -300,15 +762,65 @@ fn evaluate_template(data: &Value, record: Pair<Rule>) -> Result<String, Templat
         }
         Rule::debug_template => {
             let debug_expression = expression.into_inner().next().unwrap();
-            let debug_evaluated = parse_expression(&data, &mut debug_expression.clone().into_inner())?;
+            let debug_evaluated = parse_expression(context, &data, &mut debug_expression.clone().into_inner())?;
             info!("{}", format!("Debug expression: {} = {}", debug_expression.as_str().trim(), debug_evaluated))
         }
         Rule::expression_template => {
             let mut inner_rules = expression.into_inner();
             let expression = inner_rules.next().unwrap();
-            let evaluation_result = parse_expression(&data, &mut expression.into_inner())?;
-            result.push_str(format_string(&evaluation_result).to_string().as_str())
+            let evaluation_result = parse_expression(context, &data, &mut expression.into_inner())?;
+            result.push_str(function::format_string(&evaluation_result).to_string().as_str())
+        }
+        Rule::include_template => {
+            let mut include_inner = expression.into_inner();
+            let include_path_expression = include_inner.next().unwrap();
+            let include_path = parse_expression(context, data, &mut include_path_expression.clone().into_inner())?;
+            let include_path_string = include_path.as_str()
+                .ok_or_else(|| locate_error(context, &include_path_expression, TemplateRenderError::IncludeError(
+                    format!("The template path must be a string, but got {}", function::describe(&include_path)))))?;
+            let include_data = match include_inner.next() {
+                Some(with_expression) => parse_expression(context, data, &mut with_expression.into_inner())?,
+                None => data.clone(),
+            };
+            result.push_str(evaluate_include(context, &include_data, include_path_string)?.as_str())
+        }
+        Rule::block_template => {
+            let name = expression.clone().into_inner().next().unwrap().as_str();
+            let mut definitions: Vec<Definition> = context.blocks.iter()
+                .filter_map(|blocks| blocks.get(name).cloned())
+                .collect();
+            if definitions.is_empty() {
+                definitions.push(Definition {
+                    template_path: context.template_paths.last().unwrap().clone(),
+                    pair: expression,
+                });
+            }
+
+            let parent_blocks = definitions.split_off(1);
+            let definition = definitions.remove(0);
+            let mut block_context = definition.context(context);
+            block_context.parent_blocks = parent_blocks;
+            result.push_str(evaluate_block(&block_context, data, definition.pair)?.as_str())
+        }
+        Rule::super_template => {
+            let (parent_block, parent_blocks) = context.parent_blocks.split_first()
+                .ok_or_else(|| locate_error(context, &expression, TemplateRenderError::ExtendError(
+                    "'super' can only be used in a block that overrides a block of an extended template".to_string())))?;
+            let mut block_context = parent_block.context(context);
+            block_context.parent_blocks = parent_blocks.to_vec();
+            result.push_str(evaluate_block(&block_context, data, parent_block.pair.clone())?.as_str())
+        }
+        Rule::raw_template => {
+            let mut raw_inner = expression.into_inner();
+            let raw_content = raw_inner.next().unwrap();
+            let endraw_template = raw_inner.next().unwrap();
+            result.push_str(trim_before_tag(raw_content.as_str(), &endraw_template).as_str())
+        }
+        Rule::escaped_start_marker => {
+            result.push_str(expression.as_str().strip_suffix('%').unwrap())
         }
+        Rule::extends_template => (),
+        Rule::macro_template => (),
         Rule::comment => (),
         _ => unreachable!(),
     }
@@ -316,13 +828,93 @@ fn evaluate_template(data: &Value, record: Pair<Rule>) -> Result<String, Templat
     return Ok(result);
 }
 
-pub fn evaluate_file(data: &Value, file: Pair<Rule>) -> Result<String, TemplateRenderError> {
+fn evaluate_block<'a>(context: &Context<'a>, data: &Value, block: Pair<'a, Rule>) -> Result<String, TemplateRenderError> {
+    let mut result = String::new();
+
+    for block_inner in block.into_inner() {
+        match block_inner.as_rule() {
+            Rule::function | Rule::property => (),
+            Rule::end_template => {
+                result = trim_before_tag(&result, &block_inner);
+            }
+            Rule::character => {
+                result.push_str(block_inner.as_str())
+            }
+            Rule::template => {
+                let template_result = evaluate_template(context, data, block_inner)?;
+                result.push_str(template_result.as_str());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(result)
+}
+
+/// The first definition of each named block in the template, including nested blocks.
+fn find_blocks<'a>(template_path: &Path, file: &Pair<'a, Rule>) -> HashMap<String, Definition<'a>> {
+    let mut blocks = HashMap::new();
+    for pair in file.clone().into_inner().flatten() {
+        if pair.as_rule() == Rule::block_template {
+            let name = pair.clone().into_inner().next().unwrap().as_str().to_string();
+            blocks.entry(name).or_insert(Definition { template_path: template_path.to_path_buf(), pair });
+        }
+    }
+    blocks
+}
+
+/// The macros defined in the template, including macros defined within other blocks.
+fn find_macros<'a>(template_path: &Path, file: &Pair<'a, Rule>) -> HashMap<String, Definition<'a>> {
+    let mut macros = HashMap::new();
+    for pair in file.clone().into_inner().flatten() {
+        if pair.as_rule() == Rule::macro_template {
+            let name = pair.clone().into_inner().next().unwrap().as_str().to_string();
+            macros.entry(name).or_insert(Definition { template_path: template_path.to_path_buf(), pair });
+        }
+    }
+    macros
+}
+
+fn find_extends<'a>(file: &Pair<'a, Rule>) -> Option<Pair<'a, Rule>> {
+    file.clone().into_inner()
+        .filter(|record| record.as_rule() == Rule::template)
+        .map(|record| record.into_inner().next().unwrap())
+        .find(|template| template.as_rule() == Rule::extends_template)
+}
+
+fn evaluate_file_with_context<'a>(context: &Context<'a>, data: &Value, file: Pair<'a, Rule>) -> Result<String, TemplateRenderError> {
+    let template_path = context.template_paths.last().unwrap();
+    let mut file_context = context.clone();
+    for (name, definition) in find_macros(template_path, &file) {
+        // The macros of an extending template take precedence over the macros of the extended template
+        if context.blocks.is_empty() {
+            file_context.macros.insert(name, definition);
+        } else {
+            file_context.macros.entry(name).or_insert(definition);
+        }
+    }
+
+    if let Some(extends) = find_extends(&file) {
+        // The content outside the blocks of an extending template is not rendered
+        let parent_path_expression = extends.into_inner().next().unwrap();
+        let parent_path = parse_expression(&file_context, data, &mut parent_path_expression.clone().into_inner())?;
+        let parent_path_string = parent_path.as_str()
+            .ok_or_else(|| locate_error(&file_context, &parent_path_expression, TemplateRenderError::ExtendError(
+                format!("The template path must be a string, but got {}", function::describe(&parent_path)))))?;
+        file_context.blocks.push(find_blocks(template_path, &file));
+        return evaluate_extends(&file_context, data, parent_path_string);
+    }
+
+    if !context.blocks.is_empty() {
+        file_context.blocks.push(find_blocks(template_path, &file));
+    }
+
     let mut result = String::new();
 
     for record in file.into_inner() {
         match record.as_rule() {
             Rule::template => {
-                let evaluation = evaluate_template(&data, record)?;
+                let evaluation = evaluate_template(&file_context, &data, record)?;
                 result.push_str(evaluation.as_str())
             }
             Rule::character => {
@@ -336,6 +928,96 @@ pub fn evaluate_file(data: &Value, file: Pair<Rule>) -> Result<String, TemplateR
     return Ok(result);
 }
 
+/// Resolves the path of an included or extended template relative to the directory of the current template.
+fn resolve_template_path(context: &Context, template_path: &str) -> Result<PathBuf, String> {
+    let current_path = context.template_paths.last().unwrap();
+    let path = current_path.parent().unwrap_or(Path::new("")).join(template_path);
+
+    let canonical_path = fs::canonicalize(&path).ok();
+    if canonical_path.is_some() && context.template_paths.iter().any(|template_path| fs::canonicalize(template_path).ok() == canonical_path) {
+        let chain = context.template_paths.iter()
+            .chain([&path])
+            .map(|template_path| format!("'{}'", template_path.display()))
+            .join(" -> ");
+        return Err(format!("Cycle detected: {}", chain));
+    }
+
+    Ok(path)
+}
+
+fn read_template_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path)
+        .map_err(|error| format!("Could not read template file '{}': {}", path.display(), error))
+}
+
+fn parse_template_file<'a>(path: &Path, source: &'a TemplateSource) -> Result<Pair<'a, Rule>, String> {
+    source.parse()
+        .map(|mut file| file.next().unwrap())
+        .map_err(|error| format!("Could not parse template\n{}", (*error).with_path(path.to_str().unwrap_or("<path not representable in UTF-8>"))))
+}
+
+fn evaluate_include(context: &Context, data: &Value, include_path: &str) -> Result<String, TemplateRenderError> {
+    let path = resolve_template_path(context, include_path)
+        .map_err(TemplateRenderError::IncludeError)?;
+    let source = read_template_file(&path)
+        .map(|content| TemplateSource::new(&content, &context.options))
+        .map_err(TemplateRenderError::IncludeError)?;
+    let file = parse_template_file(&path, &source)
+        .map_err(TemplateRenderError::IncludeError)?;
+
+    // Blocks of the including template do not apply to the included template
+    let included_context = Context {
+        template_paths: [context.template_paths.clone(), vec![path.clone()]].concat(),
+        blocks: vec![],
+        parent_blocks: vec![],
+        macros: context.macros.clone(),
+        macro_depth: context.macro_depth,
+        options: context.options.clone(),
+    };
+    evaluate_file_with_context(&included_context, data, file)
+        .map_err(|error| TemplateRenderError::IncludedTemplateError(path.display().to_string(), Box::new(error)))
+}
+
+fn evaluate_extends(context: &Context, data: &Value, parent_path: &str) -> Result<String, TemplateRenderError> {
+    let path = resolve_template_path(context, parent_path)
+        .map_err(TemplateRenderError::ExtendError)?;
+    let source = read_template_file(&path)
+        .map(|content| TemplateSource::new(&content, &context.options))
+        .map_err(TemplateRenderError::ExtendError)?;
+    let file = parse_template_file(&path, &source)
+        .map_err(TemplateRenderError::ExtendError)?;
+
+    let mut parent_context = context.clone();
+    parent_context.template_paths.push(path.clone());
+    evaluate_file_with_context(&parent_context, data, file)
+        .map_err(|error| TemplateRenderError::ExtendedTemplateError(path.display().to_string(), Box::new(error)))
+}
+
+/// Evaluates the template file. Included and extended templates are resolved relative to the working directory.
+pub fn evaluate_file(data: &Value, file: Pair<Rule>) -> Result<String, TemplateRenderError> {
+    evaluate_file_with_path(data, file, Path::new("<template>"))
+}
+
+/// Evaluates the template file read from the path. Included and extended templates are resolved relative to the
+/// directory of the path.
+pub fn evaluate_file_with_path(data: &Value, file: Pair<Rule>, template_path: &Path) -> Result<String, TemplateRenderError> {
+    evaluate_file_with_options(data, file, template_path, &Options::default())
+}
+
+/// Evaluates the template file with the given options. The template content must be parsed with the same options,
+/// see `TemplateSource`.
+pub fn evaluate_file_with_options(data: &Value, file: Pair<Rule>, template_path: &Path, options: &Options) -> Result<String, TemplateRenderError> {
+    let context = Context {
+        template_paths: vec![template_path.to_path_buf()],
+        blocks: vec![],
+        parent_blocks: vec![],
+        macros: HashMap::new(),
+        macro_depth: 0,
+        options: options.clone(),
+    };
+    evaluate_file_with_context(&context, data, file)
+}
+
 pub fn parse_template(template_content: &String) -> Result<Pairs<Rule>, pest::error::Error<Rule>> {
     TemplateParser::parse(Rule::file, &template_content)
 }
\ No newline at end of file
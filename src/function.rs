@@ -1,14 +1,66 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::ops::Index;
 
 use chrono::{DateTime, Local};
 use itertools::Itertools;
 use regex::Regex;
-use serde_json::{Map, Value};
+use serde_json::{json, Map, Number, Value};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-use crate::error::TemplateRenderError;
+use crate::error::{ArgumentTypeError, TemplateRenderError};
+use crate::operator;
 
-fn type_of<T>(_: &T) -> String {
-    format!("{}", std::any::type_name::<T>())
+const MAXIMUM_PREVIEW_LENGTH: usize = 40;
+const MAXIMUM_CACHED_REGEXES: usize = 256;
+const MAXIMUM_REPEATED_LENGTH: usize = 1 << 28;
+
+thread_local! {
+    /// Compiled regular expressions by their pattern, so a pattern in a template is compiled only once, even when
+    /// the function is applied many times in a loop.
+    static REGEX_CACHE: RefCell<HashMap<String, Regex>> = RefCell::new(HashMap::new());
+}
+
+/// Compiles the regular expression, or takes it from the cache when it was compiled before.
+fn compile_regex(pattern: &str) -> Result<Regex, TemplateRenderError> {
+    REGEX_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(regex) = cache.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = Regex::new(pattern).map_err(|_err| TemplateRenderError::InvalidRegexError(pattern.to_string()))?;
+        // Patterns built from data could grow the cache without bounds
+        if cache.len() >= MAXIMUM_CACHED_REGEXES {
+            cache.clear();
+        }
+        cache.insert(pattern.to_string(), regex.clone());
+        Ok(regex)
+    })
+}
+
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "dictionary",
+    }
+}
+
+/// Formats the value as it is rendered in a template.
+pub fn format_string(value: &Value) -> String {
+    match value {
+        Value::Null => "".to_string(),
+        Value::Bool(boolean) => boolean.to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::String(string) => string.to_string(),
+        Value::Array(values) => format!("[{}]", values.iter().map(|v| format_string(v)).reduce(|cur, next| format!("{},{}", &cur, &next)).unwrap_or("".to_string())),
+        Value::Object(object) => format!("{{{}}}", object.iter().map(|(k, v)| format!("{}:{}", k, format_string(v))).reduce(|cur, next| format!("{},{}", &cur, &next)).unwrap_or("".to_string())),
+    }
 }
 
 pub fn to_boolean(value: &Value) -> bool {
@@ -33,13 +85,13 @@ pub fn to_boolean(value: &Value) -> bool {
 }
 
 fn kebab_case(value: &String) -> String {
-    let re = Regex::new(r"[^a-zA-Z0-9_]+").unwrap();
+    let re = compile_regex(r"[^a-zA-Z0-9_]+").unwrap();
     let lower = value.to_lowercase();
     return re.replace_all(lower.as_str(), "-").to_string();
 }
 
 fn snake_case(value: &String) -> String {
-    let re = Regex::new(r"[^a-zA-Z0-9-]+").unwrap();
+    let re = compile_regex(r"[^a-zA-Z0-9-]+").unwrap();
     let lower = value.to_lowercase();
     return re.replace_all(lower.as_str(), "_").to_string();
 }
@@ -73,11 +125,9 @@ fn pascal_case(value: &String) -> String {
 }
 
 fn capitalize(value: &String) -> String {
-    if value.is_empty() {
-        value.as_str().to_string()
-    } else {
-        let (first, rest) = value.split_at(1);
-        format!("{}{}", first.to_uppercase(), rest)
+    match value.chars().next() {
+        None => String::new(),
+        Some(first) => format!("{}{}", first.to_uppercase(), &value[first.len_utf8()..]),
     }
 }
 
@@ -96,6 +146,415 @@ fn capitalize_words(value: &String) -> String {
     return result;
 }
 
+/// Parses a boolean, ignoring case and surrounding whitespace. When lenient, `yes`, `no`, `y`, `n`, `on`, `off`, `1` and `0` are parsed as well.
+fn parse_boolean(string: &str, lenient: bool) -> Option<bool> {
+    match string.trim().to_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        "yes" | "y" | "on" | "1" if lenient => Some(true),
+        "no" | "n" | "off" | "0" if lenient => Some(false),
+        _ => None,
+    }
+}
+
+/// Parses an integer, ignoring surrounding whitespace. The integer may have a sign, and a `0x`, `0o` or `0b` prefix for
+/// hexadecimal, octal or binary notation.
+fn parse_integer(string: &str) -> Option<Value> {
+    let trimmed = string.trim();
+    let (negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let lower = unsigned.to_lowercase();
+    let (radix, digits) = if let Some(digits) = lower.strip_prefix("0x") {
+        (16, digits)
+    } else if let Some(digits) = lower.strip_prefix("0o") {
+        (8, digits)
+    } else if let Some(digits) = lower.strip_prefix("0b") {
+        (2, digits)
+    } else {
+        (10, lower.as_str())
+    };
+    if digits.is_empty() || !digits.chars().all(|digit| digit.is_digit(radix)) {
+        return None;
+    }
+    let magnitude = i128::from_str_radix(digits, radix).ok()?;
+    let integer = if negative { -magnitude } else { magnitude };
+    i64::try_from(integer).map(Value::from).ok()
+        .or_else(|| u64::try_from(integer).map(Value::from).ok())
+}
+
+/// Parses a finite decimal number, ignoring surrounding whitespace.
+fn parse_decimal(string: &str) -> Option<f64> {
+    let trimmed = string.trim();
+    let is_decimal = trimmed.chars().all(|character| character.is_ascii_digit() || "+-.eE".contains(character))
+        && trimmed.chars().any(|character| character.is_ascii_digit());
+    if !is_decimal {
+        return None;
+    }
+    trimmed.parse::<f64>().ok().filter(|decimal| decimal.is_finite())
+}
+
+fn decimal_value(decimal: f64) -> Value {
+    Number::from_f64(decimal).map(Value::Number).unwrap_or(Value::Null)
+}
+
+/// Applies an arithmetic operator to the value and the first argument, which must both be numbers.
+fn apply_arithmetic_function(function: &str, value: &Value, arguments: &Vec<Value>, operator: &str) -> Result<Value, TemplateRenderError> {
+    require_number(function, 0, value)?;
+    require_number_argument(function, arguments, 0)?;
+    operator::apply_binary_operator(value, operator, &arguments[0])
+}
+
+/// Converts a floating point number without a fractional part to an integer.
+fn float_to_integer(float: f64, description: &str) -> Result<Value, TemplateRenderError> {
+    if !float.is_finite() {
+        return Err(TemplateRenderError::ArithmeticError(format!("Result of {} is not a finite number", description)));
+    }
+    operator::integer_value(float as i128, description)
+}
+
+/// Rounds a number to an integer with the rounding function. Integers are returned as is.
+fn round_number(function: &str, number: &Number, round: fn(f64) -> f64) -> Result<Value, TemplateRenderError> {
+    if operator::integer_of(number).is_some() {
+        Ok(Value::Number(number.clone()))
+    } else {
+        float_to_integer(round(number.as_f64().unwrap()), format!("{}({})", function, number).as_str())
+    }
+}
+
+/// The numbers of the value and the argument, or the numbers in the array value when there are no arguments.
+fn numbers_of<'a>(function: &str, value: &'a Value, arguments: &'a Vec<Value>) -> Result<Vec<&'a Value>, TemplateRenderError> {
+    let numbers: Vec<&Value> = if arguments.is_empty() {
+        require_array(function, 0, value)?.iter().collect()
+    } else {
+        vec![value, require_argument(function, arguments, 0)?]
+    };
+    for (position, number) in numbers.iter().enumerate() {
+        if !number.is_number() {
+            let position = if arguments.is_empty() { 0 } else { position };
+            return Err(type_error(function, position, if arguments.is_empty() { &["array of numbers"] } else { &["number"] }, number));
+        }
+    }
+    Ok(numbers)
+}
+
+/// The smallest or largest of the numbers, or `null` when there are no numbers.
+fn extreme_number(numbers: Vec<&Value>, ordering: Ordering) -> Value {
+    numbers.into_iter()
+        .reduce(|extreme, number| if operator::compare(number, extreme) == Some(ordering) { number } else { extreme })
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+/// Folds the numbers of the array value with an arithmetic operator.
+fn aggregate_numbers(function: &str, value: &Value, operator: &str, initial: Value) -> Result<Value, TemplateRenderError> {
+    let no_arguments = vec![];
+    let numbers = numbers_of(function, value, &no_arguments)?;
+    numbers.into_iter()
+        .try_fold(initial, |result, number| operator::apply_binary_operator(&result, operator, number))
+}
+
+/// The value at a dot separated path like `address.city` or `hosts.0.name`, if it exists.
+pub fn value_at_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |current, key| match current {
+        Value::Object(object) => object.get(key),
+        Value::Array(array) => key.parse::<usize>().ok().and_then(|index| array.get(index)),
+        _ => None,
+    })
+}
+
+/// The value at the path of the item, or `null` when it does not exist.
+fn path_value<'a>(item: &'a Value, path: &str) -> &'a Value {
+    value_at_path(item, path).unwrap_or(&Value::Null)
+}
+
+/// Whether the value at the path of the item equals the value, or is truthy when no value is given.
+fn item_matches(item: &Value, path: &str, value: Option<&Value>) -> bool {
+    match value {
+        Some(value) => operator::equals(path_value(item, path), value),
+        None => to_boolean(path_value(item, path)),
+    }
+}
+
+fn flatten_array(array: &[Value], depth: u64) -> Vec<Value> {
+    array.iter()
+        .flat_map(|item| match item {
+            Value::Array(items) if depth > 0 => flatten_array(items, depth - 1),
+            _ => vec![item.clone()],
+        })
+        .collect()
+}
+
+/// Merges the other value into the value. Dictionaries are merged recursively, and arrays according to the
+/// strategy: `replace` uses the other array, `append` adds the items of the other array, and `merge` merges the items
+/// with the same index. Other values are replaced by the other value.
+fn deep_merge(value: &Value, other: &Value, strategy: &str) -> Value {
+    match (value, other) {
+        (Value::Object(object), Value::Object(other_object)) => {
+            let mut result = object.clone();
+            for (key, other_item) in other_object {
+                let merged = match object.get(key) {
+                    Some(item) => deep_merge(item, other_item, strategy),
+                    None => other_item.clone(),
+                };
+                result.insert(key.clone(), merged);
+            }
+            Value::Object(result)
+        }
+        (Value::Array(array), Value::Array(other_array)) if strategy == "append" => {
+            Value::Array(array.iter().chain(other_array).cloned().collect())
+        }
+        (Value::Array(array), Value::Array(other_array)) if strategy == "merge" => {
+            let merged = (0..array.len().max(other_array.len()))
+                .map(|index| match (array.get(index), other_array.get(index)) {
+                    (Some(item), Some(other_item)) => deep_merge(item, other_item, strategy),
+                    (Some(item), None) | (None, Some(item)) => item.clone(),
+                    (None, None) => unreachable!(),
+                })
+                .collect();
+            Value::Array(merged)
+        }
+        _ => other.clone(),
+    }
+}
+
+/// Sets the value at the path of keys. Missing keys and `null` values become dictionaries.
+fn set_at_path(target: &mut Value, keys: &[&str], value: Value) -> Result<(), TemplateRenderError> {
+    let Some((key, remaining_keys)) = keys.split_first() else {
+        *target = value;
+        return Ok(());
+    };
+    if target.is_null() {
+        *target = Value::Object(Map::new());
+    }
+    let child = match target {
+        Value::Object(object) => object.entry(key.to_string()).or_insert(Value::Null),
+        Value::Array(array) => {
+            let length = array.len();
+            key.parse::<usize>().ok()
+                .and_then(|index| array.get_mut(index))
+                .ok_or_else(|| TemplateRenderError::ArgumentValueError(format!("Cannot set index '{}' of an array with {} items", key, length)))?
+        }
+        _ => return Err(TemplateRenderError::ArgumentValueError(format!("Cannot set property '{}' of {}", key, describe(target)))),
+    };
+    set_at_path(child, remaining_keys, value)
+}
+
+/// Splits the string into characters, or into grapheme clusters when the argument is `graphemes`. A grapheme cluster
+/// is what is displayed as a single character, like a letter with combining accents or an emoji with modifiers.
+fn string_units<'a>(function: &str, string: &'a str, arguments: &Vec<Value>, index: usize) -> Result<Vec<&'a str>, TemplateRenderError> {
+    let graphemes = match arguments.get(index) {
+        None | Some(Value::Null) => false,
+        Some(_) => match require_string_argument(function, arguments, index)?.as_str() {
+            "characters" => false,
+            "graphemes" => true,
+            unit => return Err(TemplateRenderError::ArgumentValueError(format!("Unknown string unit '{}', expected 'characters' or 'graphemes'", unit))),
+        },
+    };
+    if graphemes {
+        Ok(string.graphemes(true).collect())
+    } else {
+        Ok(string.char_indices().map(|(position, character)| &string[position..position + character.len_utf8()]).collect())
+    }
+}
+
+/// The position of the index in a sequence of the length. Negative indexes count from the end. Indexes outside the
+/// sequence are limited to its start or end.
+fn resolve_index(index: i64, length: usize) -> usize {
+    let offset = usize::try_from(index.unsigned_abs()).unwrap_or(usize::MAX);
+    if index < 0 {
+        length.saturating_sub(offset)
+    } else {
+        offset.min(length)
+    }
+}
+
+/// The text of a string or number value, to align it.
+fn require_text(function: &str, value: &Value) -> Result<String, TemplateRenderError> {
+    match value {
+        Value::String(string) => Ok(string.clone()),
+        Value::Number(number) => Ok(number.to_string()),
+        _ => Err(type_error(function, 0, &["string", "number"], value)),
+    }
+}
+
+/// The padding argument, a space when it is not given.
+fn require_padding_argument(function: &str, arguments: &Vec<Value>, index: usize) -> Result<String, TemplateRenderError> {
+    match arguments.get(index) {
+        None | Some(Value::Null) => Ok(" ".to_string()),
+        Some(_) => {
+            let padding = require_string_argument(function, arguments, index)?;
+            if padding.width() == 0 {
+                return Err(TemplateRenderError::ArgumentValueError(format!("The padding '{}' must be at least one column wide", padding)));
+            }
+            Ok(padding.clone())
+        }
+    }
+}
+
+/// The length in bytes of a string repeated the number of times, which must not be unreasonably large.
+fn require_repeated_length(length: usize, times: usize) -> Result<usize, TemplateRenderError> {
+    length.checked_mul(times)
+        .filter(|repeated_length| *repeated_length <= MAXIMUM_REPEATED_LENGTH)
+        .ok_or_else(|| TemplateRenderError::ArgumentValueError(format!("The result would be longer than the maximum of {} bytes", MAXIMUM_REPEATED_LENGTH)))
+}
+
+/// Adds the padding around the text until it is as wide as the width, measured in columns of a terminal, so wide
+/// characters like emoji count double. `left_share` is the share of the padding on the left, between 0 and 2.
+fn pad_text(text: &str, width: usize, padding: &str, left_share: usize) -> Result<String, TemplateRenderError> {
+    let paddings = width.saturating_sub(text.width()) / padding.width();
+    require_repeated_length(padding.len(), paddings)?;
+    let left_paddings = paddings * left_share / 2;
+    Ok(format!("{}{}{}", padding.repeat(left_paddings), text, padding.repeat(paddings - left_paddings)))
+}
+
+/// Indents every line that is not empty.
+fn indent_lines(string: &str, width: usize) -> Result<String, TemplateRenderError> {
+    require_repeated_length(width, string.split('\n').count())?;
+    let indentation = " ".repeat(width);
+    let indented = string.split('\n')
+        .map(|line| if line.trim().is_empty() { line.to_string() } else { format!("{}{}", indentation, line) })
+        .join("\n");
+    Ok(indented)
+}
+
+/// Wraps each line at the whitespace between words, so the lines are at most as wide as the width. Words wider than the
+/// width are put on their own line.
+fn wrap_lines(string: &str, width: usize) -> String {
+    string.split('\n')
+        .map(|line| {
+            let mut wrapped_lines = vec![];
+            let mut current_line = String::new();
+            for word in line.split_whitespace() {
+                if !current_line.is_empty() && current_line.width() + 1 + word.width() > width {
+                    wrapped_lines.push(std::mem::take(&mut current_line));
+                }
+                if !current_line.is_empty() {
+                    current_line.push(' ');
+                }
+                current_line.push_str(word);
+            }
+            wrapped_lines.push(current_line);
+            wrapped_lines.join("\n")
+        })
+        .join("\n")
+}
+
+/// How strings are ordered when sorting.
+#[derive(Clone, Copy, Default)]
+struct StringOrdering {
+    /// Numbers in strings are ordered by their numeric value, so `eth2` comes before `eth10`.
+    natural: bool,
+    ignore_case: bool,
+}
+
+/// Parses the comma separated sort options, like `"natural, ignoreCase"`, from the argument if it is given.
+fn string_ordering(function: &str, arguments: &Vec<Value>, index: usize) -> Result<StringOrdering, TemplateRenderError> {
+    let mut ordering = StringOrdering::default();
+    if arguments.get(index).is_some_and(|argument| !argument.is_null()) {
+        let options = require_string_argument(function, arguments, index)?;
+        for option in options.split(',').map(str::trim).filter(|option| !option.is_empty()) {
+            match option {
+                "natural" => ordering.natural = true,
+                "ignoreCase" => ordering.ignore_case = true,
+                _ => return Err(TemplateRenderError::ArgumentValueError(format!("Unknown sort option '{}', expected 'natural' or 'ignoreCase'", option))),
+            }
+        }
+    }
+    Ok(ordering)
+}
+
+/// Splits a string into alternating parts of digits and other characters.
+fn natural_parts(string: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut previous_digit = None;
+    for (index, character) in string.char_indices() {
+        let digit = character.is_ascii_digit();
+        if previous_digit.is_some_and(|previous| previous != digit) {
+            parts.push(&string[start..index]);
+            start = index;
+        }
+        previous_digit = Some(digit);
+    }
+    if start < string.len() {
+        parts.push(&string[start..]);
+    }
+    parts
+}
+
+fn compare_natural(left: &str, right: &str) -> Ordering {
+    let (left_parts, right_parts) = (natural_parts(left), natural_parts(right));
+    left_parts.iter().zip(right_parts.iter())
+        .map(|(left_part, right_part)| {
+            if left_part.starts_with(|character: char| character.is_ascii_digit()) && right_part.starts_with(|character: char| character.is_ascii_digit()) {
+                let (left_digits, right_digits) = (left_part.trim_start_matches('0'), right_part.trim_start_matches('0'));
+                left_digits.len().cmp(&right_digits.len()).then_with(|| left_digits.cmp(right_digits))
+            } else {
+                left_part.cmp(right_part)
+            }
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| left_parts.len().cmp(&right_parts.len()))
+}
+
+fn compare_strings(left: &str, right: &str, ordering: StringOrdering) -> Ordering {
+    let (left, right) = if ordering.ignore_case {
+        (left.to_lowercase(), right.to_lowercase())
+    } else {
+        (left.to_string(), right.to_string())
+    };
+    if ordering.natural {
+        compare_natural(&left, &right)
+    } else {
+        left.cmp(&right)
+    }
+}
+
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+/// A total order of all values. Values of different types are ordered `null`, booleans, numbers, strings, arrays and
+/// dictionaries. Arrays are ordered by their items, and dictionaries by their entries.
+fn compare_values(left: &Value, right: &Value, ordering: StringOrdering) -> Ordering {
+    match (left, right) {
+        (Value::String(left_string), Value::String(right_string)) => compare_strings(left_string, right_string, ordering),
+        (Value::Array(left_array), Value::Array(right_array)) => left_array.iter().zip(right_array)
+            .map(|(left_item, right_item)| compare_values(left_item, right_item, ordering))
+            .find(|item_ordering| item_ordering.is_ne())
+            .unwrap_or_else(|| left_array.len().cmp(&right_array.len())),
+        (Value::Object(left_object), Value::Object(right_object)) => left_object.iter().zip(right_object)
+            .map(|((left_key, left_item), (right_key, right_item))| compare_strings(left_key, right_key, ordering)
+                .then_with(|| compare_values(left_item, right_item, ordering)))
+            .find(|entry_ordering| entry_ordering.is_ne())
+            .unwrap_or_else(|| left_object.len().cmp(&right_object.len())),
+        _ => operator::compare(left, right)
+            .unwrap_or_else(|| type_rank(left).cmp(&type_rank(right))),
+    }
+}
+
+/// Sorts the array stably, by the value at the path of each item when the path is given.
+fn sort_array(array: &[Value], path: Option<&str>, ordering: StringOrdering, descending: bool) -> Value {
+    let mut sorted = array.to_vec();
+    sorted.sort_by(|left, right| {
+        let item_ordering = match path {
+            Some(path) => compare_values(path_value(left, path), path_value(right, path), ordering),
+            None => compare_values(left, right, ordering),
+        };
+        if descending { item_ordering.reverse() } else { item_ordering }
+    });
+    Value::Array(sorted)
+}
+
 fn environment(value: &String) -> Option<String> {
     return std::env::var(value.as_str()).ok();
 }
@@ -108,79 +567,330 @@ fn default(value: &Value, default: &Value) -> Value {
     }
 }
 
-fn require_string_value(value: &Value) -> Result<&String, TemplateRenderError> {
+/// The type and the preview of the value, like `string "abc"`, or only the type for `null`.
+pub(crate) fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => type_name(value).to_string(),
+        _ => format!("{} {}", type_name(value), preview(value)),
+    }
+}
+
+/// The value as compact JSON, truncated when it is long.
+fn preview(value: &Value) -> String {
+    let json = value.to_string();
+    if json.chars().count() <= MAXIMUM_PREVIEW_LENGTH {
+        json
+    } else {
+        format!("{}…", json.chars().take(MAXIMUM_PREVIEW_LENGTH - 1).collect::<String>())
+    }
+}
+
+/// An error for a value of the wrong type given to a function. Position 0 is the value the function is applied to,
+/// and position 1 and further are the arguments of the function.
+fn type_error(function: &str, position: usize, expected: &[&'static str], value: &Value) -> TemplateRenderError {
+    TemplateRenderError::ArgumentTypeError(ArgumentTypeError {
+        function: function.to_string(),
+        position,
+        expected: expected.to_vec(),
+        actual: type_name(value),
+        preview: preview(value),
+    })
+}
+
+fn require_string<'a>(function: &str, position: usize, value: &'a Value) -> Result<&'a String, TemplateRenderError> {
     match value {
         Value::String(string) => Ok(string),
-        _ => Err(TemplateRenderError::TypeError(type_of(&value)))
+        _ => Err(type_error(function, position, &["string"], value))
     }
 }
 
-fn require_u64_value(value: &Value) -> Result<u64, TemplateRenderError> {
+fn require_u64(function: &str, position: usize, value: &Value) -> Result<u64, TemplateRenderError> {
     value.as_u64()
-        .ok_or_else(|| TemplateRenderError::TypeError(type_of(&value)))
+        .ok_or_else(|| type_error(function, position, &["non-negative integer"], value))
+}
+
+fn require_i64(function: &str, position: usize, value: &Value) -> Result<i64, TemplateRenderError> {
+    value.as_i64()
+        .ok_or_else(|| type_error(function, position, &["integer"], value))
 }
 
-fn require_array_value(value: &Value) -> Result<&Vec<Value>, TemplateRenderError> {
+fn require_array<'a>(function: &str, position: usize, value: &'a Value) -> Result<&'a Vec<Value>, TemplateRenderError> {
     value.as_array()
-        .ok_or_else(|| TemplateRenderError::TypeError(type_of(&value)))
+        .ok_or_else(|| type_error(function, position, &["array"], value))
 }
 
-fn require_object_value(value: &Value) -> Result<&Map<String, Value>, TemplateRenderError> {
+fn require_object<'a>(function: &str, position: usize, value: &'a Value) -> Result<&'a Map<String, Value>, TemplateRenderError> {
     value.as_object()
-        .ok_or_else(|| TemplateRenderError::TypeError(type_of(&value)))
+        .ok_or_else(|| type_error(function, position, &["dictionary"], value))
+}
+
+fn require_number<'a>(function: &str, position: usize, value: &'a Value) -> Result<&'a Number, TemplateRenderError> {
+    match value {
+        Value::Number(number) => Ok(number),
+        _ => Err(type_error(function, position, &["number"], value))
+    }
+}
+
+fn require_string_value<'a>(function: &str, value: &'a Value) -> Result<&'a String, TemplateRenderError> {
+    require_string(function, 0, value)
+}
+
+fn require_u64_value(function: &str, value: &Value) -> Result<u64, TemplateRenderError> {
+    require_u64(function, 0, value)
 }
 
-fn require_argument<'a>(function: &'a str, arguments: &'a Vec<Value>, index: usize) -> Result<&'a Value, TemplateRenderError> {
+pub(crate) fn require_array_value<'a>(function: &str, value: &'a Value) -> Result<&'a Vec<Value>, TemplateRenderError> {
+    require_array(function, 0, value)
+}
+
+fn require_number_value<'a>(function: &str, value: &'a Value) -> Result<&'a Number, TemplateRenderError> {
+    require_number(function, 0, value)
+}
+
+pub(crate) fn require_object_value<'a>(function: &str, value: &'a Value) -> Result<&'a Map<String, Value>, TemplateRenderError> {
+    require_object(function, 0, value)
+}
+
+fn require_argument<'a>(function: &str, arguments: &'a Vec<Value>, index: usize) -> Result<&'a Value, TemplateRenderError> {
     arguments.get(index)
         .ok_or_else(||
             TemplateRenderError::RequiredArgumentMissing(format!("Argument {} is missing for function '{}'", index + 1, function.to_string()))
         )
 }
 
+fn require_string_argument<'a>(function: &str, arguments: &'a Vec<Value>, index: usize) -> Result<&'a String, TemplateRenderError> {
+    require_string(function, index + 1, require_argument(function, arguments, index)?)
+}
+
+fn require_u64_argument(function: &str, arguments: &Vec<Value>, index: usize) -> Result<u64, TemplateRenderError> {
+    require_u64(function, index + 1, require_argument(function, arguments, index)?)
+}
+
+fn require_i64_argument(function: &str, arguments: &Vec<Value>, index: usize) -> Result<i64, TemplateRenderError> {
+    require_i64(function, index + 1, require_argument(function, arguments, index)?)
+}
+
+fn require_number_argument<'a>(function: &str, arguments: &'a Vec<Value>, index: usize) -> Result<&'a Number, TemplateRenderError> {
+    require_number(function, index + 1, require_argument(function, arguments, index)?)
+}
+
+fn require_object_argument<'a>(function: &str, arguments: &'a Vec<Value>, index: usize) -> Result<&'a Map<String, Value>, TemplateRenderError> {
+    require_object(function, index + 1, require_argument(function, arguments, index)?)
+}
+
+/// A single key, or an array of keys.
+fn require_keys_argument<'a>(function: &str, arguments: &'a Vec<Value>, index: usize) -> Result<Vec<&'a str>, TemplateRenderError> {
+    let argument = require_argument(function, arguments, index)?;
+    match argument {
+        Value::String(key) => Ok(vec![key.as_str()]),
+        Value::Array(keys) => keys.iter()
+            .map(|key| key.as_str().ok_or_else(|| type_error(function, index + 1, &["string", "array of strings"], argument)))
+            .collect(),
+        _ => Err(type_error(function, index + 1, &["string", "array of strings"], argument)),
+    }
+}
+
+fn require_regex_argument(function: &str, arguments: &Vec<Value>, index: usize) -> Result<Regex, TemplateRenderError> {
+    compile_regex(require_string_argument(function, arguments, index)?)
+}
+
+fn require_array_argument<'a>(function: &str, arguments: &'a Vec<Value>, index: usize) -> Result<&'a Vec<Value>, TemplateRenderError> {
+    require_array(function, index + 1, require_argument(function, arguments, index)?)
+}
+
 pub fn apply_function(value: &Value, function: &str, arguments: &Vec<Value>) -> Result<Value, TemplateRenderError> {
     return match function {
         "lowerCase" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(string.to_lowercase()))
         }
         "upperCase" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(string.to_uppercase()))
         }
         "kebabCase" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(kebab_case(string)))
         }
         "snakeCase" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(snake_case(string)))
         }
         "camelCase" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(camel_case(string)))
         }
         "pascalCase" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(pascal_case(string)))
         }
         "capitalize" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(capitalize(string)))
         }
         "capitalizeWords" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(capitalize_words(string)))
         }
         "length" => {
             match value {
-                Value::String(string) => Ok(Value::from(string.len())),
+                Value::String(string) => Ok(Value::from(string_units(function, string, arguments, 0)?.len())),
                 Value::Array(array) => Ok(Value::from(array.len())),
                 Value::Object(dictionary) => Ok(Value::from(dictionary.len())),
-                _ => Err(TemplateRenderError::TypeError(type_of(&value)))
+                _ => Err(type_error(function, 0, &["string", "array", "dictionary"], value))
+            }
+        }
+        "toString" => {
+            Ok(Value::String(format_string(value)))
+        }
+        "parseBoolean" => {
+            let lenient = match arguments.first() {
+                Some(argument) => to_boolean(argument),
+                None => false,
+            };
+            match value {
+                Value::Null | Value::Bool(_) => Ok(value.clone()),
+                Value::String(string) => parse_boolean(string, lenient)
+                    .map(Value::Bool)
+                    .ok_or_else(|| TemplateRenderError::ArgumentValueError(format!("Could not parse '{}' as a boolean", string))),
+                _ => Err(type_error(function, 0, &["string", "boolean"], value)),
+            }
+        }
+        "parseInteger" => {
+            match value {
+                Value::Null => Ok(Value::Null),
+                Value::Number(number) if number.is_i64() || number.is_u64() => Ok(value.clone()),
+                Value::Number(number) => {
+                    let decimal = number.as_f64().unwrap();
+                    if decimal.fract() == 0.0 && decimal >= i64::MIN as f64 && decimal <= i64::MAX as f64 {
+                        Ok(Value::from(decimal as i64))
+                    } else {
+                        Err(TemplateRenderError::ArgumentValueError(format!("Could not convert {} to an integer", number)))
+                    }
+                }
+                Value::String(string) => parse_integer(string)
+                    .ok_or_else(|| TemplateRenderError::ArgumentValueError(format!("Could not parse '{}' as an integer", string))),
+                _ => Err(type_error(function, 0, &["string", "number"], value)),
+            }
+        }
+        "parseDecimal" => {
+            match value {
+                Value::Null => Ok(Value::Null),
+                Value::Number(number) => Ok(decimal_value(number.as_f64().unwrap())),
+                Value::String(string) => parse_decimal(string)
+                    .map(decimal_value)
+                    .ok_or_else(|| TemplateRenderError::ArgumentValueError(format!("Could not parse '{}' as a decimal number", string))),
+                _ => Err(type_error(function, 0, &["string", "number"], value)),
+            }
+        }
+        "parseNumber" => {
+            match value {
+                Value::Null | Value::Number(_) => Ok(value.clone()),
+                Value::String(string) => parse_integer(string)
+                    .or_else(|| parse_decimal(string).map(decimal_value))
+                    .ok_or_else(|| TemplateRenderError::ArgumentValueError(format!("Could not parse '{}' as a number", string))),
+                _ => Err(type_error(function, 0, &["string", "number"], value)),
+            }
+        }
+        "add" => apply_arithmetic_function(function, value, arguments, "+"),
+        "subtract" => apply_arithmetic_function(function, value, arguments, "-"),
+        "multiply" => apply_arithmetic_function(function, value, arguments, "*"),
+        "divide" => apply_arithmetic_function(function, value, arguments, "/"),
+        "modulo" => apply_arithmetic_function(function, value, arguments, "%"),
+        "abs" => {
+            let number = require_number_value(function, value)?;
+            match operator::integer_of(number) {
+                Some(integer) => operator::integer_value(integer.abs(), format!("abs({})", number).as_str()),
+                None => operator::float_value(number.as_f64().unwrap().abs(), format!("abs({})", number).as_str()),
+            }
+        }
+        "round" => {
+            let number = require_number_value(function, value)?;
+            match arguments.first() {
+                None => round_number(function, number, f64::round),
+                Some(_) => {
+                    let digits = require_u64_argument(function, arguments, 0)?;
+                    if operator::integer_of(number).is_some() {
+                        return Ok(Value::Number(number.clone()));
+                    }
+                    let factor = 10f64.powi(digits.min(i32::MAX as u64) as i32);
+                    let scaled = number.as_f64().unwrap() * factor;
+                    // The number has no digits to round when the scaled number exceeds the range of a float
+                    if !scaled.is_finite() {
+                        return Ok(Value::Number(number.clone()));
+                    }
+                    operator::float_value(scaled.round() / factor, format!("round({}, {})", number, digits).as_str())
+                }
+            }
+        }
+        "floor" => {
+            let number = require_number_value(function, value)?;
+            round_number(function, number, f64::floor)
+        }
+        "ceil" => {
+            let number = require_number_value(function, value)?;
+            round_number(function, number, f64::ceil)
+        }
+        "min" => {
+            Ok(extreme_number(numbers_of(function, value, arguments)?, Ordering::Less))
+        }
+        "max" => {
+            Ok(extreme_number(numbers_of(function, value, arguments)?, Ordering::Greater))
+        }
+        "clamp" => {
+            require_number_value(function, value)?;
+            require_number_argument(function, arguments, 0)?;
+            require_number_argument(function, arguments, 1)?;
+            let (minimum, maximum) = (&arguments[0], &arguments[1]);
+            if operator::compare(minimum, maximum) == Some(Ordering::Greater) {
+                return Err(TemplateRenderError::ArgumentValueError(format!("The minimum ({}) cannot be larger than the maximum ({})", minimum, maximum)));
+            }
+            let result = if operator::compare(value, minimum) == Some(Ordering::Less) {
+                minimum
+            } else if operator::compare(value, maximum) == Some(Ordering::Greater) {
+                maximum
+            } else {
+                value
+            };
+            Ok(result.clone())
+        }
+        "pow" => {
+            let base = require_number_value(function, value)?;
+            let exponent = require_number_argument(function, arguments, 0)?;
+            let description = format!("pow({}, {})", base, exponent);
+            match (operator::integer_of(base), exponent.as_u64()) {
+                (Some(base_integer), Some(exponent_integer)) => {
+                    let result = match u32::try_from(exponent_integer) {
+                        Ok(exponent_integer) => base_integer.checked_pow(exponent_integer),
+                        // Only the bases -1, 0 and 1 do not overflow with a larger exponent
+                        Err(_) if base_integer == -1 => Some(if exponent_integer % 2 == 0 { 1 } else { -1 }),
+                        Err(_) if base_integer == 0 || base_integer == 1 => Some(base_integer),
+                        Err(_) => None,
+                    };
+                    let result = result.ok_or_else(|| TemplateRenderError::ArithmeticError(format!("Integer overflow in {}", description)))?;
+                    operator::integer_value(result, description.as_str())
+                }
+                _ => operator::float_value(base.as_f64().unwrap().powf(exponent.as_f64().unwrap()), description.as_str()),
+            }
+        }
+        "sqrt" => {
+            let number = require_number_value(function, value)?;
+            operator::float_value(number.as_f64().unwrap().sqrt(), format!("sqrt({})", number).as_str())
+        }
+        "sum" => aggregate_numbers(function, value, "+", Value::from(0)),
+        "product" => aggregate_numbers(function, value, "*", Value::from(1)),
+        "average" => {
+            let no_arguments = vec![];
+            let numbers = numbers_of(function, value, &no_arguments)?;
+            if numbers.is_empty() {
+                Ok(Value::Null)
+            } else {
+                let sum: f64 = numbers.iter().map(|number| number.as_f64().unwrap()).sum();
+                operator::float_value(sum / numbers.len() as f64, "average")
             }
         }
         "environment" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(environment(string)
                 .map(|value| Value::from(value))
                 .unwrap_or(Value::Null))
@@ -205,72 +915,71 @@ pub fn apply_function(value: &Value, function: &str, arguments: &Vec<Value>) ->
                     reverted.reverse();
                     Ok(Value::Array(reverted))
                 }
-                _ => Err(TemplateRenderError::TypeError(type_of(&value)))
+                _ => Err(type_error(function, 0, &["string", "array"], value))
             }
         }
         "split" => {
-            let string = require_string_value(value)?;
-            let splitter = require_argument(function, arguments, 0)?;
-            let splitter_string = require_string_value(splitter)?;
+            let string = require_string_value(function, value)?;
+            let splitter_string = require_string_argument(function, arguments, 0)?;
             let split_strings = string.split(splitter_string).map(|split| Value::String(split.to_string())).collect();
             Ok(Value::Array(split_strings))
         }
         "lines" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             let lines: Vec<Value> = string.trim().lines().map(|item| Value::String(item.to_string())).collect();
             Ok(Value::Array(lines))
         }
         "matches" => {
-            let string = require_string_value(value)?;
-            let regex = require_argument(function, arguments, 0)?;
-            let regex_string = require_string_value(regex)?;
-            let re = Regex::new(regex_string).map_err(|_err| TemplateRenderError::InvalidRegexError(regex_string.to_string()))?;
+            let string = require_string_value(function, value)?;
+            let re = require_regex_argument(function, arguments, 0)?;
             Ok(Value::Bool(re.is_match(string.as_str())))
         }
         "substring" => {
-            let string = require_string_value(value)?;
-            let from = require_argument(function, arguments, 0)?;
-            let from_value = require_u64_value(from)?;
-            let to = arguments.get(1);
-            if to.is_some() {
-                let to_value = require_u64_value(to.unwrap())?;
-                Ok(Value::String(string[usize::try_from(from_value).unwrap().max(0).min(string.len())..usize::try_from(to_value).unwrap().max(0).min(string.len())].to_string()))
-            } else {
-                Ok(Value::String(string[usize::try_from(from_value).unwrap().max(0).min(string.len())..].to_string()))
+            let string = require_string_value(function, value)?;
+            let units = string_units(function, string, arguments, 2)?;
+            let from_index = resolve_index(require_i64_argument(function, arguments, 0)?, units.len());
+            let to_index = match arguments.get(1) {
+                None | Some(Value::Null) => units.len(),
+                Some(_) => resolve_index(require_i64_argument(function, arguments, 1)?, units.len()),
+            };
+            if from_index > to_index {
+                return Err(TemplateRenderError::ArgumentValueError(format!("The start of the substring ({}) cannot be after its end ({})", arguments[0], arguments[1])));
             }
+            Ok(Value::String(units[from_index..to_index].concat()))
         }
         "take" => {
-            let n = require_argument(function, arguments, 0)?;
-            let n_value = require_u64_value(n)?;
-            let to_index = usize::try_from(n_value).unwrap();
+            let n_value = require_i64_argument(function, arguments, 0)?;
             match value {
-                Value::String(string) => Ok(Value::String(string[..to_index.max(0).min(string.len())].to_string())),
-                Value::Array(array) => Ok(Value::Array(array[..to_index.max(0).min(array.len())].to_vec())),
-                _ => Err(TemplateRenderError::TypeError(type_of(&value)))
+                Value::String(string) => {
+                    let units = string_units(function, string, arguments, 1)?;
+                    Ok(Value::String(units[..resolve_index(n_value, units.len())].concat()))
+                }
+                Value::Array(array) => Ok(Value::Array(array[..resolve_index(n_value, array.len())].to_vec())),
+                _ => Err(type_error(function, 0, &["string", "array"], value))
             }
         }
         "drop" => {
-            let n = require_argument(function, arguments, 0)?;
-            let n_value = require_u64_value(n)?;
-            let from_index = usize::try_from(n_value).unwrap();
+            let n_value = require_i64_argument(function, arguments, 0)?;
             match value {
-                Value::String(string) => Ok(Value::String(string[from_index.max(0).min(string.len())..].to_string())),
-                Value::Array(array) => Ok(Value::Array(array[from_index.max(0).min(array.len())..].to_vec())),
-                _ => Err(TemplateRenderError::TypeError(type_of(&value)))
+                Value::String(string) => {
+                    let units = string_units(function, string, arguments, 1)?;
+                    Ok(Value::String(units[resolve_index(n_value, units.len())..].concat()))
+                }
+                Value::Array(array) => Ok(Value::Array(array[resolve_index(n_value, array.len())..].to_vec())),
+                _ => Err(type_error(function, 0, &["string", "array"], value))
             }
         }
         "first" => {
-            let array = require_array_value(value)?;
+            let array = require_array_value(function, value)?;
             Ok(array.first().unwrap_or(&Value::Null).clone())
         }
         "last" => {
-            let array = require_array_value(value)?;
+            let array = require_array_value(function, value)?;
             Ok(array.last().unwrap_or(&Value::Null).clone())
         }
         "index" => {
-            let index = require_argument(function, arguments, 0)?;
-            let index_number = require_u64_value(index)?;
-            let array = require_array_value(value)?;
+            let index_number = require_u64_argument(function, arguments, 0)?;
+            let array = require_array_value(function, value)?;
             let result = if index_number < array.len() as u64 {
                 array.index(index_number as usize).clone()
             } else {
@@ -282,64 +991,212 @@ pub fn apply_function(value: &Value, function: &str, arguments: &Vec<Value>) ->
             let needle = require_argument(function, arguments, 0)?;
             match value {
                 Value::String(substring) => {
-                    let needle_string = require_string_value(needle)?;
+                    let needle_string = require_string(function, 1, needle)?;
                     Ok(Value::Bool(substring.contains(needle_string)))
                 }
                 Value::Array(array) => Ok(Value::Bool(array.contains(needle))),
-                _ => Err(TemplateRenderError::TypeError(type_of(&needle))),
+                _ => Err(type_error(function, 0, &["string", "array"], value)),
             }
         }
         "containsKey" => {
-            let key = require_argument(function, arguments, 0)?;
-            let object = require_object_value(value)?;
-            let key_value = require_string_value(key)?;
+            let object = require_object_value(function, value)?;
+            let key_value = require_string_argument(function, arguments, 0)?;
             Ok(Value::Bool(object.contains_key(key_value)))
         }
         "containsValue" => {
             let needle = require_argument(function, arguments, 0)?;
-            let object = require_object_value(value)?;
+            let object = require_object_value(function, value)?;
             Ok(Value::Bool(object.values().any(|val| val == needle)))
         }
         "startsWith" => {
-            let string = require_string_value(value)?;
-            let start = require_argument(function, arguments, 0)?;
-            let start_string = require_string_value(start)?;
+            let string = require_string_value(function, value)?;
+            let start_string = require_string_argument(function, arguments, 0)?;
             Ok(Value::Bool(string.starts_with(start_string)))
         }
         "endsWith" => {
-            let string = require_string_value(value)?;
-            let end = require_argument(function, arguments, 0)?;
-            let end_string = require_string_value(end)?;
+            let string = require_string_value(function, value)?;
+            let end_string = require_string_argument(function, arguments, 0)?;
             Ok(Value::Bool(string.ends_with(end_string)))
         }
         "empty" => {
             Ok(Value::Bool(!to_boolean(value)))
         }
+        "sort" | "sortDescending" => {
+            let array = require_array_value(function, value)?;
+            let ordering = string_ordering(function, arguments, 0)?;
+            Ok(sort_array(array, None, ordering, function == "sortDescending"))
+        }
+        "sortBy" | "sortByDescending" => {
+            let array = require_array_value(function, value)?;
+            let path = require_string_argument(function, arguments, 0)?;
+            let ordering = string_ordering(function, arguments, 1)?;
+            Ok(sort_array(array, Some(path), ordering, function == "sortByDescending"))
+        }
+        "pluck" => {
+            let array = require_array_value(function, value)?;
+            let path = require_string_argument(function, arguments, 0)?;
+            Ok(Value::Array(array.iter().map(|item| path_value(item, path).clone()).collect()))
+        }
+        "where" | "whereNot" => {
+            let array = require_array_value(function, value)?;
+            let path = require_string_argument(function, arguments, 0)?;
+            let matching = function == "where";
+            Ok(Value::Array(array.iter()
+                .filter(|item| item_matches(item, path, arguments.get(1)) == matching)
+                .cloned()
+                .collect()))
+        }
+        "partition" => {
+            let array = require_array_value(function, value)?;
+            let path = require_string_argument(function, arguments, 0)?;
+            let (matching, other): (Vec<Value>, Vec<Value>) = array.iter()
+                .cloned()
+                .partition(|item| item_matches(item, path, arguments.get(1)));
+            Ok(Value::Array(vec![Value::Array(matching), Value::Array(other)]))
+        }
+        "groupBy" => {
+            let array = require_array_value(function, value)?;
+            let path = require_string_argument(function, arguments, 0)?;
+            let mut groups = Map::new();
+            for item in array {
+                let group = groups.entry(format_string(path_value(item, path)))
+                    .or_insert_with(|| Value::Array(vec![]));
+                if let Value::Array(items) = group {
+                    items.push(item.clone());
+                }
+            }
+            Ok(Value::Object(groups))
+        }
+        "countBy" => {
+            let array = require_array_value(function, value)?;
+            let path = require_string_argument(function, arguments, 0)?;
+            let mut counts = Map::new();
+            for item in array {
+                let count = counts.entry(format_string(path_value(item, path)))
+                    .or_insert(Value::from(0));
+                *count = Value::from(count.as_u64().unwrap() + 1);
+            }
+            Ok(Value::Object(counts))
+        }
+        "flatten" => {
+            let array = require_array_value(function, value)?;
+            let depth = match arguments.first() {
+                Some(_) => require_u64_argument(function, arguments, 0)?,
+                None => 1,
+            };
+            Ok(Value::Array(flatten_array(array, depth)))
+        }
+        "compact" => {
+            let array = require_array_value(function, value)?;
+            Ok(Value::Array(array.iter().filter(|item| to_boolean(item)).cloned().collect()))
+        }
+        "concat" => {
+            let array = require_array_value(function, value)?;
+            let other = require_array_argument(function, arguments, 0)?;
+            Ok(Value::Array(array.iter().chain(other).cloned().collect()))
+        }
+        "zip" => {
+            let array = require_array_value(function, value)?;
+            let other = require_array_argument(function, arguments, 0)?;
+            Ok(Value::Array(array.iter().zip(other)
+                .map(|(item, other_item)| Value::Array(vec![item.clone(), other_item.clone()]))
+                .collect()))
+        }
         "unique" => {
-            let array = require_array_value(value)?;
+            let array = require_array_value(function, value)?;
             let unique = array.clone().into_iter()
                 .unique_by(|item| format!("{item}"))
                 .collect::<Vec<_>>();
             Ok(Value::Array(unique))
         }
         "keys" => {
-            let object = require_object_value(value)?;
+            let object = require_object_value(function, value)?;
             let keys = object.keys().into_iter().map(|key| Value::String(key.clone())).collect::<Vec<_>>();
             Ok(Value::Array(keys))
         }
         "values" => {
-            let object = require_object_value(value)?;
+            let object = require_object_value(function, value)?;
             Ok(Value::Array(object.values().cloned().into_iter().collect::<Vec<_>>()))
         }
         "invert" => {
-            let object = require_object_value(value)?;
+            let object = require_object_value(function, value)?;
             if let Some(item) = object.values().into_iter().find(|value| !value.is_string()) {
-                Err(TemplateRenderError::TypeError(type_of(&item)))
+                Err(type_error(function, 0, &["dictionary of strings"], item))
             } else {
                 let inverted = object.clone().into_iter().map(|(key, value)| (value.as_str().unwrap().to_string(), Value::String(key))).collect();
                 Ok(Value::Object(inverted))
             }
         }
+        "merge" => {
+            let object = require_object_value(function, value)?;
+            let other = require_object_argument(function, arguments, 0)?;
+            let mut merged = object.clone();
+            merged.extend(other.clone());
+            Ok(Value::Object(merged))
+        }
+        "deepMerge" => {
+            require_object_value(function, value)?;
+            require_object_argument(function, arguments, 0)?;
+            let strategy = match arguments.get(1) {
+                Some(_) => require_string_argument(function, arguments, 1)?.as_str(),
+                None => "replace",
+            };
+            if !matches!(strategy, "replace" | "append" | "merge") {
+                return Err(TemplateRenderError::ArgumentValueError(format!("Unknown array merge strategy '{}', expected 'replace', 'append' or 'merge'", strategy)));
+            }
+            Ok(deep_merge(value, &arguments[0], strategy))
+        }
+        "pick" | "omit" => {
+            let object = require_object_value(function, value)?;
+            let keys = require_keys_argument(function, arguments, 0)?;
+            let picking = function == "pick";
+            Ok(Value::Object(object.iter()
+                .filter(|(key, _)| keys.contains(&key.as_str()) == picking)
+                .map(|(key, item)| (key.clone(), item.clone()))
+                .collect()))
+        }
+        "get" => {
+            let path = require_string_argument(function, arguments, 0)?;
+            let default = arguments.get(1).unwrap_or(&Value::Null);
+            Ok(value_at_path(value, path).unwrap_or(default).clone())
+        }
+        "set" => {
+            let path = require_string_argument(function, arguments, 0)?;
+            let new_value = require_argument(function, arguments, 1)?;
+            let keys: Vec<&str> = path.split('.').collect();
+            let mut result = value.clone();
+            set_at_path(&mut result, &keys, new_value.clone())?;
+            Ok(result)
+        }
+        "entries" => {
+            let object = require_object_value(function, value)?;
+            Ok(Value::Array(object.iter()
+                .map(|(key, item)| json!({"key": key, "value": item}))
+                .collect()))
+        }
+        "fromEntries" => {
+            let array = require_array_value(function, value)?;
+            let mut result = Map::new();
+            for entry in array {
+                let (key, item) = match entry {
+                    Value::Object(object) => (object.get("key").unwrap_or(&Value::Null), object.get("value").unwrap_or(&Value::Null)),
+                    Value::Array(pair) if pair.len() == 2 => (&pair[0], &pair[1]),
+                    _ => return Err(type_error(function, 0, &["array of entries"], entry)),
+                };
+                let key = key.as_str()
+                    .ok_or_else(|| TemplateRenderError::ArgumentValueError(format!("The key of entry {} must be a string", preview(entry))))?;
+                result.insert(key.to_string(), item.clone());
+            }
+            Ok(Value::Object(result))
+        }
+        "filterKeys" => {
+            let object = require_object_value(function, value)?;
+            let re = require_regex_argument(function, arguments, 0)?;
+            Ok(Value::Object(object.iter()
+                .filter(|(key, _)| re.is_match(key))
+                .map(|(key, item)| (key.clone(), item.clone()))
+                .collect()))
+        }
         "toJson" => {
             let result = serde_json::to_string(value)
                 .map_err(|_| TemplateRenderError::JsonSerializationError)?;
@@ -351,75 +1208,167 @@ pub fn apply_function(value: &Value, function: &str, arguments: &Vec<Value>) ->
             Ok(Value::String(result))
         }
         "fromJson" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             serde_json::from_str(string.as_str())
                 .map_err(|error| TemplateRenderError::JsonParseError(error.to_string()))
         }
         "abbreviate" => {
-            let n = require_argument(function, arguments, 0)?;
-            let string = require_string_value(value)?;
-            let n_value = require_u64_value(n)?;
-            if string.len() <= n_value as usize {
+            let string = require_string_value(function, value)?;
+            let n_value = usize::try_from(require_u64_argument(function, arguments, 0)?).unwrap_or(usize::MAX);
+            let units = string_units(function, string, arguments, 1)?;
+            if units.len() <= n_value {
                 Ok(Value::String(string.clone()))
             } else {
-                Ok(Value::String(format!("{}…", string.as_str()[0..((n_value.max(1) as usize) - 1)].to_string())))
+                Ok(Value::String(format!("{}…", units[..n_value.max(1) - 1].concat())))
+            }
+        }
+        "padLeft" | "padRight" | "center" => {
+            let text = require_text(function, value)?;
+            let width = usize::try_from(require_u64_argument(function, arguments, 0)?).unwrap_or(usize::MAX);
+            let padding = require_padding_argument(function, arguments, 1)?;
+            let left_share = match function {
+                "padLeft" => 2,
+                "center" => 1,
+                _ => 0,
+            };
+            Ok(Value::String(pad_text(&text, width, &padding, left_share)?))
+        }
+        "repeat" => {
+            let string = require_string_value(function, value)?;
+            let times = usize::try_from(require_u64_argument(function, arguments, 0)?).unwrap_or(usize::MAX);
+            require_repeated_length(string.len(), times)?;
+            Ok(Value::String(string.repeat(times)))
+        }
+        "indent" | "nindent" => {
+            let string = require_string_value(function, value)?;
+            let width = usize::try_from(require_u64_argument(function, arguments, 0)?).unwrap_or(usize::MAX);
+            let newline = if function == "nindent" { "\n" } else { "" };
+            Ok(Value::String(format!("{}{}", newline, indent_lines(string, width)?)))
+        }
+        "wrap" => {
+            let string = require_string_value(function, value)?;
+            let width = usize::try_from(require_u64_argument(function, arguments, 0)?).unwrap_or(usize::MAX);
+            Ok(Value::String(wrap_lines(string, width)))
+        }
+        "truncateWords" => {
+            let string = require_string_value(function, value)?;
+            let words = usize::try_from(require_u64_argument(function, arguments, 0)?).unwrap_or(usize::MAX);
+            let suffix = match arguments.get(1) {
+                Some(_) => require_string_argument(function, arguments, 1)?.as_str(),
+                None => "…",
+            };
+            let mut word_ends = compile_regex(r"\S+").unwrap().find_iter(string).map(|word| word.end()).collect::<Vec<_>>();
+            if word_ends.len() <= words {
+                Ok(Value::String(string.clone()))
+            } else {
+                word_ends.truncate(words);
+                let end = word_ends.last().copied().unwrap_or(0);
+                Ok(Value::String(format!("{}{}", &string[..end], suffix)))
             }
         }
         "trimLeft" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(string.trim_start().to_string()))
         }
         "trimRight" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(string.trim_end().to_string()))
         }
         "trim" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
             Ok(Value::String(string.trim().to_string()))
         }
         "replace" => {
-            let string = require_string_value(value)?;
-            let search = require_argument(function, arguments, 0)?;
-            let search_string = require_string_value(search)?;
-            let replacement = require_argument(function, arguments, 1)?;
-            let replacement_string = require_string_value(replacement)?;
+            let string = require_string_value(function, value)?;
+            let search_string = require_string_argument(function, arguments, 0)?;
+            let replacement_string = require_string_argument(function, arguments, 1)?;
             Ok(Value::String(string.replace(search_string, replacement_string)))
         }
         "regexReplace" => {
-            let string = require_string_value(value)?;
-            let regex = require_argument(function, arguments, 0)?;
-            let regex_string = require_string_value(regex)?;
-            let parsed_regex = Regex::new(regex_string)
-                .map_err(|_| TemplateRenderError::InvalidRegexError(regex_string.to_string()))?;
-            let replacement = require_argument(function, arguments, 1)?;
-            let replacement_string = require_string_value(replacement)?;
+            let string = require_string_value(function, value)?;
+            let parsed_regex = require_regex_argument(function, arguments, 0)?;
+            let replacement_string = require_string_argument(function, arguments, 1)?;
             Ok(Value::String(parsed_regex.replace_all(string.as_str(), replacement_string).to_string()))
         }
+        "regexFind" => {
+            let string = require_string_value(function, value)?;
+            let re = require_regex_argument(function, arguments, 0)?;
+            Ok(re.find(string).map(|found| Value::String(found.as_str().to_string())).unwrap_or(Value::Null))
+        }
+        "regexFindAll" => {
+            let string = require_string_value(function, value)?;
+            let re = require_regex_argument(function, arguments, 0)?;
+            Ok(Value::Array(re.find_iter(string).map(|found| Value::String(found.as_str().to_string())).collect()))
+        }
+        "regexCaptures" => {
+            let string = require_string_value(function, value)?;
+            let re = require_regex_argument(function, arguments, 0)?;
+            let Some(captures) = re.captures(string) else {
+                return Ok(Value::Null);
+            };
+            let group = |index: usize| captures.get(index).map(|group| Value::String(group.as_str().to_string())).unwrap_or(Value::Null);
+            if re.capture_names().flatten().next().is_some() {
+                // Named groups by their name, and the other groups by their number
+                let groups = re.capture_names().enumerate()
+                    .map(|(index, name)| (name.map(str::to_string).unwrap_or(index.to_string()), group(index)))
+                    .collect();
+                Ok(Value::Object(groups))
+            } else {
+                Ok(Value::Array((0..captures.len()).map(group).collect()))
+            }
+        }
+        "regexSplit" => {
+            let string = require_string_value(function, value)?;
+            let re = require_regex_argument(function, arguments, 0)?;
+            Ok(Value::Array(re.split(string).map(|split| Value::String(split.to_string())).collect()))
+        }
         "negate" => {
             Ok(Value::Bool(!to_boolean(value)))
         }
+        "map" | "filter" | "select" | "mapValues" | "mapKeys" => {
+            Err(TemplateRenderError::ArgumentValueError(format!("Function '{}' expects a lambda argument, like {}(x => x)", function, function)))
+        }
         "all" => {
-            let result = require_array_value(value)?;
+            let result = require_array_value(function, value)?;
             Ok(Value::Bool(result.into_iter().all(|item| to_boolean(item))))
         }
         "any" => {
-            let result = require_array_value(value)?;
+            let result = require_array_value(function, value)?;
             Ok(Value::Bool(result.into_iter().any(|item| to_boolean(item))))
         }
         "none" => {
-            let result = require_array_value(value)?;
+            let result = require_array_value(function, value)?;
             Ok(Value::Bool(result.into_iter().all(|item| !to_boolean(item))))
         }
         "some" => {
-            let result = require_array_value(value)?;
+            let result = require_array_value(function, value)?;
             Ok(Value::Bool(result.into_iter().any(|item| !to_boolean(item))))
         }
+        "join" => {
+            let array = require_array_value(function, value)?;
+            let separator = require_string_argument(function, arguments, 0)?;
+            let last_separator = match arguments.get(1) {
+                None | Some(Value::Null) => separator,
+                Some(_) => require_string_argument(function, arguments, 1)?,
+            };
+            let nested = arguments.get(2).is_some_and(to_boolean);
+
+            let mut result = String::new();
+            for (index, item) in array.iter().enumerate() {
+                if !nested && (item.is_array() || item.is_object()) {
+                    return Err(TemplateRenderError::ArgumentValueError(format!("Cannot join the nested value {} at index {}", preview(item), index)));
+                }
+                if index > 0 {
+                    result.push_str(if index == array.len() - 1 { last_separator } else { separator });
+                }
+                result.push_str(format_string(item).as_str());
+            }
+            Ok(Value::String(result))
+        }
         "chunked" => {
-            let array = require_array_value(value)?;
-            let chunk_size = require_argument(function, arguments, 0)?;
-            let chunk_size_number = require_u64_value(chunk_size)? as usize;
-            let overlap = require_argument(function, arguments, 1)?;
-            let overlap_number = require_u64_value(overlap)? as usize;
+            let array = require_array_value(function, value)?;
+            let chunk_size_number = require_u64_argument(function, arguments, 0)? as usize;
+            let overlap_number = require_u64_argument(function, arguments, 1)? as usize;
 
             if overlap_number >= chunk_size_number {
                 Err(TemplateRenderError::ArgumentValueError(format!("The overlap ({overlap_number}) cannot be equal or larger than the chunk size ({chunk_size_number})")))
@@ -436,27 +1385,24 @@ pub fn apply_function(value: &Value, function: &str, arguments: &Vec<Value>) ->
             }
         }
         "parseFormatDateTime" => {
-            let string = require_string_value(value)?;
+            let string = require_string_value(function, value)?;
 
             let parse_result = if string == "now" {
                 // First argument is ignored
                 DateTime::from(Local::now())
             } else {
-                let parse_format = require_argument(function, arguments, 0)?;
-                let parse_format_string = require_string_value(parse_format)?;
+                let parse_format_string = require_string_argument(function, arguments, 0)?;
                 DateTime::parse_from_str(string, parse_format_string)
                     .map_err(|err| TemplateRenderError::ArgumentValueError(format!("Could not parse date-time with value '{string}' and parse format string '{parse_format_string}': {err}")))?
             };
 
-            let format = require_argument(function, arguments, 1)?;
-            let format_string = require_string_value(format)?;
+            let format_string = require_string_argument(function, arguments, 1)?;
             let formatted = parse_result.format(format_string).to_string();
             Ok(Value::String(formatted))
         }
         "alternate" => {
-            let index = require_u64_value(value)?;
-            let items = require_argument(function, arguments, 0)?;
-            let items_array = require_array_value(items)?;
+            let index = require_u64_value(function, value)?;
+            let items_array = require_array_argument(function, arguments, 0)?;
 
             if items_array.is_empty() {
                 Ok(Value::Null)
@@ -469,8 +1415,7 @@ pub fn apply_function(value: &Value, function: &str, arguments: &Vec<Value>) ->
         }
         "assert" => {
             let expected_value = require_argument(function, arguments, 0)?;
-            let message = require_argument(function, arguments, 1)?;
-            let message_string = require_string_value(message)?;
+            let message_string = require_string_argument(function, arguments, 1)?;
 
             if value != expected_value {
                 Err(TemplateRenderError::AssertionError(format!("Expected value '{}' but found '{}': {}", expected_value, value, message_string)))
@@ -4,4 +4,5 @@ extern crate pest_derive;
 
 pub mod evaluate;
 pub mod function;
+pub mod operator;
 pub mod error;
@@ -0,0 +1,146 @@
+use std::cmp::Ordering;
+
+use serde_json::{Number, Value};
+
+use crate::error::TemplateRenderError;
+use crate::function;
+
+fn operator_type_error(left: &Value, operator: &str, right: &Value) -> TemplateRenderError {
+    TemplateRenderError::TypeError(format!("{} {} {}", function::type_name(left), operator, function::type_name(right)))
+}
+
+pub(crate) fn integer_of(number: &Number) -> Option<i128> {
+    number.as_i64().map(i128::from)
+        .or_else(|| number.as_u64().map(i128::from))
+}
+
+pub(crate) fn integer_value(integer: i128, description: &str) -> Result<Value, TemplateRenderError> {
+    if let Ok(result) = i64::try_from(integer) {
+        Ok(Value::from(result))
+    } else if let Ok(result) = u64::try_from(integer) {
+        Ok(Value::from(result))
+    } else {
+        Err(TemplateRenderError::ArithmeticError(format!("Integer overflow in {}", description)))
+    }
+}
+
+pub(crate) fn float_value(float: f64, description: &str) -> Result<Value, TemplateRenderError> {
+    Number::from_f64(float)
+        .map(Value::Number)
+        .ok_or_else(|| TemplateRenderError::ArithmeticError(format!("Result of {} is not a finite number", description)))
+}
+
+/// Applies an arithmetic operator to two numbers.
+///
+/// When both operands are integers the calculation is done with integers, and the result is an integer.
+/// Division of two integers truncates towards zero.
+/// When one of the operands is a floating point number, both operands are promoted to floating point numbers.
+fn apply_arithmetic_operator(left: &Value, operator: &str, right: &Value) -> Result<Value, TemplateRenderError> {
+    let (left_number, right_number) = match (left, right) {
+        (Value::Number(left_number), Value::Number(right_number)) => (left_number, right_number),
+        _ => return Err(operator_type_error(left, operator, right)),
+    };
+    let description = format!("{} {} {}", left_number, operator, right_number);
+
+    if let (Some(left_integer), Some(right_integer)) = (integer_of(left_number), integer_of(right_number)) {
+        if right_integer == 0 && (operator == "/" || operator == "%") {
+            return Err(TemplateRenderError::ArithmeticError(format!("Division by zero in {}", description)));
+        }
+        let result = match operator {
+            "+" => left_integer.checked_add(right_integer),
+            "-" => left_integer.checked_sub(right_integer),
+            "*" => left_integer.checked_mul(right_integer),
+            "/" => left_integer.checked_div(right_integer),
+            "%" => left_integer.checked_rem(right_integer),
+            _ => unreachable!(),
+        };
+        let integer = result
+            .ok_or_else(|| TemplateRenderError::ArithmeticError(format!("Integer overflow in {}", description)))?;
+        integer_value(integer, description.as_str())
+    } else {
+        let left_float = left_number.as_f64().unwrap();
+        let right_float = right_number.as_f64().unwrap();
+        if right_float == 0.0 && (operator == "/" || operator == "%") {
+            return Err(TemplateRenderError::ArithmeticError(format!("Division by zero in {}", description)));
+        }
+        let result = match operator {
+            "+" => left_float + right_float,
+            "-" => left_float - right_float,
+            "*" => left_float * right_float,
+            "/" => left_float / right_float,
+            "%" => left_float % right_float,
+            _ => unreachable!(),
+        };
+        float_value(result, description.as_str())
+    }
+}
+
+fn compare_numbers(left: &Number, right: &Number) -> Ordering {
+    if let (Some(left_integer), Some(right_integer)) = (integer_of(left), integer_of(right)) {
+        left_integer.cmp(&right_integer)
+    } else {
+        left.as_f64().unwrap().total_cmp(&right.as_f64().unwrap())
+    }
+}
+
+/// Whether two values are equal. Numbers are equal when they have the same numeric value, regardless
+/// of their representation as integer or floating point number. Arrays and dictionaries are compared item by item.
+pub fn equals(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left_number), Value::Number(right_number)) => compare_numbers(left_number, right_number) == Ordering::Equal,
+        (Value::Array(left_array), Value::Array(right_array)) =>
+            left_array.len() == right_array.len() && left_array.iter().zip(right_array).all(|(left_item, right_item)| equals(left_item, right_item)),
+        (Value::Object(left_object), Value::Object(right_object)) =>
+            left_object.len() == right_object.len() && left_object.iter().all(|(key, left_item)| right_object.get(key).is_some_and(|right_item| equals(left_item, right_item))),
+        _ => left == right,
+    }
+}
+
+/// The ordering of two values of the same type. Numbers are ordered numerically, strings lexicographically by
+/// their characters, booleans with `false` before `true`, and arrays lexicographically by their items.
+/// Two `null` values are equal. Other combinations of values have no ordering.
+pub fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        (Value::Bool(left_boolean), Value::Bool(right_boolean)) => Some(left_boolean.cmp(right_boolean)),
+        (Value::Number(left_number), Value::Number(right_number)) => Some(compare_numbers(left_number, right_number)),
+        (Value::String(left_string), Value::String(right_string)) => Some(left_string.cmp(right_string)),
+        (Value::Array(left_array), Value::Array(right_array)) => {
+            for (left_item, right_item) in left_array.iter().zip(right_array) {
+                match compare(left_item, right_item)? {
+                    Ordering::Equal => (),
+                    ordering => return Some(ordering),
+                }
+            }
+            Some(left_array.len().cmp(&right_array.len()))
+        }
+        _ => None,
+    }
+}
+
+fn apply_comparison_operator(left: &Value, operator: &str, right: &Value) -> Result<Value, TemplateRenderError> {
+    let result = match operator {
+        "==" => equals(left, right),
+        "!=" => !equals(left, right),
+        _ => {
+            let ordering = compare(left, right)
+                .ok_or_else(|| operator_type_error(left, operator, right))?;
+            match operator {
+                "<" => ordering == Ordering::Less,
+                "<=" => ordering != Ordering::Greater,
+                ">" => ordering == Ordering::Greater,
+                ">=" => ordering != Ordering::Less,
+                _ => unreachable!(),
+            }
+        }
+    };
+    Ok(Value::Bool(result))
+}
+
+pub fn apply_binary_operator(left: &Value, operator: &str, right: &Value) -> Result<Value, TemplateRenderError> {
+    match operator {
+        "+" | "-" | "*" | "/" | "%" => apply_arithmetic_operator(left, operator, right),
+        "==" | "!=" | "<" | "<=" | ">" | ">=" => apply_comparison_operator(left, operator, right),
+        _ => unreachable!(),
+    }
+}
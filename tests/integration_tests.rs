@@ -94,6 +94,8 @@ Options:
   -t, --template <TEMPLATE>            Absolute or relative path to the template file
   -c, --configuration <CONFIGURATION>  Absolute or relative path to the configuration file. Provide `-` as path to read the configuration input from the standard input stream
   -f, --format <FORMAT>                Specify the format of the configuration input. Useful when the configuration file has a non-standard extension, or when the input is given in the standard input stream [possible values: json, hcl, yaml]
+  -d, --delimiters <DELIMITERS>        The start and end delimiters of template tags, separated by whitespace. A delimiters directive in the template file takes precedence [default: "{% %}"]
+  -s, --strict                         Fail when the template references an undefined property
   -h, --help                           Print help
   -V, --version                        Print version
 "#)
@@ -526,7 +528,9 @@ nested value 2
 
 loop end
 
+else
 
+else
 
   0-based index: 0
   1-based index: 1
@@ -547,6 +551,31 @@ loop end
 $"#).unwrap());
 }
 
+#[test]
+fn iteration_else() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/iteration_else.template")
+        .arg("--configuration")
+        .arg("tests/configuration/iteration.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"items: [value 1;value 2;]
+null: [none outer]
+dictionary: [none]
+- value 1
+- value 2
+- none
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/iteration_else.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/iteration.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
 #[test]
 fn comments() {
     let mut cmd = Command::cargo_bin("template").unwrap();
@@ -561,6 +590,7 @@ fn comments() {
         .success()
         .stdout(r#"
 false
+else
 "#)
         .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/comments.template'
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
@@ -585,7 +615,11 @@ fn function_error() {
         .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/function_error.template'
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/function_error.json'
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
-\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid type '&serde_json::value::Value'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid type: Function 'upperCase' expects the input to be of type string, but got number 1
+     --> tests/template/function_error\.template:1:4
+      \|
+    1 \| \{% number \| upperCase %\}
+      \|    \^-----------------\^
 $"#).unwrap());
 }
 
@@ -859,7 +893,7 @@ array: [,a,1,1.0,1,string]
 dictionary: {}
 dictionary: {}
 dictionary: {a:}
-dictionary: {" spaceee ":space!,a:b,c:1,d:,e:0.1,integer:1,string:string}
+dictionary: {a:b,c:1,d:,e:0.1, spaceee :space!,integer:1,string:string}
 "#)
         .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/literals.template'
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/literals.json'
@@ -921,6 +955,10 @@ fn unknown_function_call() {
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Unknown function 'doesNotExist'
+     --> tests/template/unknown_function\.template:1:4
+      \|
+    1 \| \{% "test" \| doesNotExist %\}
+      \|    \^--------------------\^
 $"#).unwrap());
 }
 
@@ -941,6 +979,10 @@ fn invalid_chunked_arguments() {
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid arguments: The overlap \(6\) cannot be equal or larger than the chunk size \(3\)
+     --> tests/template/invalid_chunked_arguments\.template:1:4
+      \|
+    1 \| \{% \[\] \| chunked\(3, 6\) %\}
+      \|    \^----------------\^
 $"#).unwrap());
 }
 
@@ -1029,6 +1071,10 @@ fn test_assert() {
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Assertion failed: Expected value 'true' but found 'false': This is not OK
+     --> tests/template/assert\.template:2:4
+      \|
+    2 \| \{% false \| assert\(true, "This is not OK"\) %\}
+      \|    \^------------------------------------\^
 $"#).unwrap());
 }
 
@@ -1078,3 +1124,1518 @@ fn vlan_test() {
 \[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
 $"#).unwrap());
 }
+
+#[test]
+fn arithmetic() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/arithmetic.template")
+        .arg("--configuration")
+        .arg("tests/configuration/arithmetic.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"addition: 3
+subtraction: -1
+subtraction: 2
+multiplication: 6
+division: 3
+division: 3.5
+division: -3
+modulo: 1
+modulo: -1
+modulo: 1.5
+precedence: 7
+precedence: 9
+precedence: -4
+precedence: 2
+promotion: 1.5
+promotion: 3.0
+properties: 8081
+properties: 120
+functions: 6
+functions: 3
+array: [2,4]
+loop: 100
+loop: 110
+loop: 120
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/arithmetic.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/arithmetic.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn division_by_zero() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/division_by_zero.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/division_by_zero.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Arithmetic error: Division by zero in 1 / 0
+     --> tests/template/division_by_zero\.template:1:4
+      \|
+    1 \| \{% 1 / 0 %\}
+      \|    \^---\^
+$"#).unwrap());
+}
+
+#[test]
+fn arithmetic_overflow() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/arithmetic_overflow.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/arithmetic_overflow.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Arithmetic error: Integer overflow in 9223372036854775807 \* 4
+     --> tests/template/arithmetic_overflow\.template:1:4
+      \|
+    1 \| \{% 9223372036854775807 \* 4 %\}
+      \|    \^---------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn comparison() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/comparison.template")
+        .arg("--configuration")
+        .arg("tests/configuration/vlan.yml")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"equals: true
+equals: true
+equals: true
+equals: true
+equals: true
+equals: true
+equals: true
+equals: false
+not equals: true
+not equals: false
+less: true
+less: false
+less or equal: true
+greater: true
+greater: true
+greater or equal: false
+greater or equal: true
+and: true
+and: false
+or: true
+or: false
+not: false
+not: true
+not: true
+precedence: false
+precedence: true
+precedence: false
+precedence: true
+vlan 10
+vlan 20
+vlan 30 pc
+vlan 40 tablet
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/comparison.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/vlan.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn comparison_error() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/comparison_error.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/comparison_error.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid type 'number < string'
+     --> tests/template/comparison_error\.template:1:4
+      \|
+    1 \| \{% 1 < "2" %\}
+      \|    \^-----\^
+$"#).unwrap());
+}
+
+#[test]
+fn conditional_expression() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/conditional_expression.template")
+        .arg("--configuration")
+        .arg("tests/configuration/conditional_expression.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"enabled: yes
+disabled: no
+comparison: large
+nested: medium
+nested: b
+selected branch: only the selected branch is evaluated
+functions: YES
+functions: YES
+dictionary: {a:1}
+item 1,
+item 2,
+item 3.
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/conditional_expression.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/conditional_expression.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn dictionary_iteration() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/dictionary_iteration.template")
+        .arg("--configuration")
+        .arg("tests/configuration/dictionary_iteration.yml")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"1/3 zone=eu-west,
+2/3 app=web,
+3/3 tier=frontend
+[zone,eu-west]
+[app,web]
+[tier,frontend]
+ssh: 22
+http: 80
+https: 443
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/dictionary_iteration.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/dictionary_iteration.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_destructuring() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_destructuring.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_destructuring.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Could not iterate: Expected an array to destructure into a, b, but got number 1
+     --> tests/template/invalid_destructuring\.template:1:16
+      \|
+    1 \| \{% for a, b in \[1\] %\}\{% a %\}\{% end %\}
+      \|                \^-\^
+$"#).unwrap());
+}
+
+#[test]
+fn include() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/include.template")
+        .arg("--configuration")
+        .arg("tests/configuration/iteration.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"header
+partial outer
+nested partial
+- value 1 (nested value 1)
+- value 2 (nested value 2)
+footer
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/include.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/iteration.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn include_cycle() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/include_cycle.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/include_cycle.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Could not include template: Cycle detected: 'tests/template/include_cycle\.template' -> 'tests/template/include/cycle_a\.template' -> 'tests/template/include/cycle_b\.template' -> 'tests/template/include/cycle_a\.template'
+     --> tests/template/include/cycle_b\.template:2:1
+      \|
+    2 \| \{% include "cycle_a\.template" %\}
+      \| \^------------------------------\^
+    in included template 'tests/template/include/cycle_b\.template'
+     --> tests/template/include/cycle_a\.template:2:1
+      \|
+    2 \| \{% include "cycle_b\.template" %\}
+      \| \^------------------------------\^
+    in included template 'tests/template/include/cycle_a\.template'
+     --> tests/template/include_cycle\.template:1:1
+      \|
+    1 \| \{% include "include/cycle_a\.template" %\}
+      \| \^--------------------------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn include_error() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/include_error.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/include_error.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Arithmetic error: Division by zero in 1 / 0
+     --> tests/template/include/error\.template:1:4
+      \|
+    1 \| \{% 1 / 0 %\}
+      \|    \^---\^
+    in included template 'tests/template/include/error\.template'
+     --> tests/template/include/error_outer\.template:1:1
+      \|
+    1 \| \{% include "error\.template" %\}
+      \| \^----------------------------\^
+    in included template 'tests/template/include/error_outer\.template'
+     --> tests/template/include_error\.template:1:1
+      \|
+    1 \| \{% include "include/error_outer\.template" %\}
+      \| \^------------------------------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn macro_include_error() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/macro_include_error.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/macro_include_error.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Arithmetic error: Division by zero in 1 / 0
+     --> tests/template/macro_include_error\.template:1:24
+      \|
+    1 \| \{% macro broken\(\) %\}\{% 1 / 0 %\}\{% end %\}
+      \|                        \^---\^
+    in included template 'tests/template/include/macro_call\.template'
+     --> tests/template/macro_include_error\.template:2:1
+      \|
+    2 \| \{% include "include/macro_call\.template" %\}
+      \| \^-----------------------------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn include_missing() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/include_missing.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/include_missing.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Could not include template: Could not read template file 'tests/template/include/does_not_exist\.template': No such file or directory \(os error 2\)
+     --> tests/template/include_missing\.template:1:1
+      \|
+    1 \| \{% include "include/does_not_exist\.template" %\}
+      \| \^---------------------------------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn extends() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/extends.template")
+        .arg("--configuration")
+        .arg("tests/configuration/hello_world.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"<title>Child title</title>
+child content
+base content: Hello
+child footer
+middle footer
+base footer
+end of base
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/extends.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/hello_world.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn extends_cycle() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/extends_cycle.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/extends_cycle.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Could not extend template: Cycle detected: 'tests/template/extends_cycle.template' -> 'tests/template/extends/cycle.template' -> 'tests/template/extends/../extends_cycle.template'
+    in extended template 'tests/template/extends/cycle.template'
+$"#).unwrap());
+}
+
+#[test]
+fn extends_error() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/extends_error.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/extends_error.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Arithmetic error: Division by zero in 1 / 0
+     --> tests/template/extends_error\.template:2:21
+      \|
+    2 \| \{% block title %\}\{% 1 / 0 %\}\{% end %\}
+      \|                     \^---\^
+    in extended template 'tests/template/extends/base\.template'
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_super() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_super.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_super.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Could not extend template: 'super' can only be used in a block that overrides a block of an extended template
+     --> tests/template/invalid_super\.template:1:31
+      \|
+    1 \| <title>\{% block title %\}Title \{% super %\}\{% end %\}</title>
+      \|                               \^---------\^
+$"#).unwrap());
+}
+
+#[test]
+fn macros() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/macros.template")
+        .arg("--configuration")
+        .arg("tests/configuration/macros.yml")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"allow ssh port 22/tcp
+allow http port 80/tcp
+allow dns port 53/udp
+labels: APP=web, TIER=frontend
+recursion: 3, 2, 1, 0
+scope: SERVICES=[{name:ssh,port:22},{name:http,port:80}]
+builtin: CALLED AS A FUNCTION
+names: allow 443, allow 8443
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/macros.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/macros.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn extends_macros() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/extends_macros.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"[Hello from child, child]
+Hello from child, outside
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/extends_macros.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn macro_recursion() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/macro_recursion.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/macro_recursion.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Maximum recursion depth exceeded when calling macro 'forever'
+     --> tests/template/macro_recursion\.template:1:26
+      \|
+    1 \| \{% macro forever\(n\) %\}\{% forever\(n \+ 1\) %\}\{% end %\}
+      \|                          \^------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn whitespace_control() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/whitespace_control.template")
+        .arg("--configuration")
+        .arg("tests/configuration/whitespace_control.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"items:
+  - a
+  - b
+  - c
+inline: [a, b, c]
+trimmed
+key: value
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/whitespace_control.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/whitespace_control.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn raw() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/raw.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"name: {% if x %}{{ value }}{% end %}
+run: echo ${{ github.sha }}
+escaped: {% value %} = rendered
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/raw.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn delimiters() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/delimiters.template")
+        .arg("--configuration")
+        .arg("tests/configuration/whitespace_control.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"jinja: {% if enabled %}{{ value }}{% endif %}
+sum: 3
+remainder: 3
+items:
+  - a
+  - b
+  - c
+escaped: <% item %>
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/delimiters.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/whitespace_control.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn delimiters_option() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/delimiters_option.template")
+        .arg("--configuration")
+        .arg("tests/configuration/whitespace_control.json")
+        .arg("--delimiters")
+        .arg("[[ ]]")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"salt: {% set name = 'value' %}
+first: A
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/delimiters_option.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/whitespace_control.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_delimiters_option() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_delimiters_option.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .arg("--delimiters")
+        .arg("[[ ]]")
+        .assert();
+
+    assert
+        .failure()
+        .code(5)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_delimiters_option.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not parse template
+     --> 1:10
+      \|
+    1 \| a \[\[ 1 \+ \]\] b
+      \|          \^---
+      \|
+      = expected pipeline
+$"#).unwrap());
+}
+
+#[test]
+fn string_escapes() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/string_escapes.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"newline: first
+second
+tab: [	]
+quote: say "hi"
+single: it's "quoted"
+unicode: café 😀
+backslash: C:\path/file
+split: [a b,c]
+replace: say 'hi'
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/string_escapes.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_string_escape() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_string_escape.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_string_escape.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Could not parse literal '"invalid \\x escape"'
+     --> tests/template/invalid_string_escape\.template:1:4
+      \|
+    1 \| \{% "invalid \\x escape" %\}
+      \|    \^-----------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn property_index() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/property_index.template")
+        .arg("--configuration")
+        .arg("tests/configuration/property_index.yml")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"index: 10.0.0.1
+negative index: second
+nested: 3
+expression: 2
+quoted key: template
+digit key: one
+key expression: spacy
+missing: [] [] []
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/property_index.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/property_index.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_property_index() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_property_index.template")
+        .arg("--configuration")
+        .arg("tests/configuration/property_index.yml")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_property_index.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/property_index.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid arguments: Index must be a string or an integer, but was 1\.5
+     --> tests/template/invalid_property_index\.template:1:13
+      \|
+    1 \| Invalid: \{% servers\[1\.5\] %\}
+      \|             \^----------\^
+$"#).unwrap());
+}
+
+#[test]
+fn undefined_property() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/strict.template")
+        .arg("--configuration")
+        .arg("tests/configuration/property_index.yml")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"defined: first
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/strict.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/property_index.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn strict_undefined_property() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/strict.template")
+        .arg("--configuration")
+        .arg("tests/configuration/property_index.yml")
+        .arg("--strict")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/strict.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/property_index.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Undefined property 'servers\[1\]\.port'
+     --> tests/template/strict\.template:2:7
+      \|
+    2 \| \{% if servers\[1\]\.port %\}
+      \|       \^-------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn strict_condition() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/strict_condition.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .arg("--strict")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/strict_condition.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Arithmetic error: Division by zero in 1 / 0
+     --> tests/template/strict_condition\.template:1:7
+      \|
+    1 \| \{% if 1 / 0 %\}
+      \|       \^---\^
+$"#).unwrap());
+}
+
+#[test]
+fn strict_optional() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/strict_optional.template")
+        .arg("--configuration")
+        .arg("tests/configuration/property_index.yml")
+        .arg("--strict")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"name: first
+port: 80
+protocol: tcp
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/strict_optional.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/property_index.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn function_argument_error() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/function_argument_error.template")
+        .arg("--configuration")
+        .arg("tests/configuration/function_argument_error.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/function_argument_error.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/function_argument_error.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid type: Function 'split' expects argument 1 to be of type string, but got array \["first item","second item","third item…
+     --> tests/template/function_argument_error\.template:1:4
+      \|
+    1 \| \{% "a,b" \| split\(items\) %\}
+      \|    \^------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn function_null_argument_error() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/function_null_argument_error.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/function_null_argument_error.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid type: Function 'upperCase' expects the input to be of type string, but got null
+     --> tests/template/function_null_argument_error\.template:1:4
+      \|
+    1 \| \{% missing \| upperCase %\}
+      \|    \^------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn conversion_functions() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/conversion_functions.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"toString: 1 1.5 true [] [1,a]
+parseBoolean: true false true false false
+parseInteger: 42 -7 31 15 -5 3 5
+parseDecimal: 1.5 -2500.0 3.0 4.0
+parseNumber: 12 255 1.25 7
+null: []
+arithmetic: 42
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/conversion_functions.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_conversion() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_conversion.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_conversion.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid arguments: Could not parse '12abc' as an integer
+     --> tests/template/invalid_conversion\.template:1:4
+      \|
+    1 \| \{% "12abc" \| parseInteger %\}
+      \|    \^---------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn math_functions() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/math_functions.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"operators: 10 -3 10.5 3 3.5 1
+abs: 3 3 2.5 9223372036854775808
+round: 3 -3 3.14 4 3.0 1.25 5 9007199254740993
+floor and ceil: 2 -3 3 -2 5
+min and max: 3 5 2.5 -1 7.5 
+clamp: 10 0 5 1
+pow: 1024 -27 0.5 2.0 9223372036854775808 1 0 -1
+sqrt: 4.0 1.414
+sum: 10 3.5 0
+product: 24 1
+average: 2.5 
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/math_functions.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn math_overflow() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/math_overflow.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/math_overflow.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Arithmetic error: Integer overflow in pow\(9223372036854775807, 2\)
+     --> tests/template/math_overflow\.template:1:4
+      \|
+    1 \| \{% 9223372036854775807 \| pow\(2\) %\}
+      \|    \^--------------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn join() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/join.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"a, b, c
+a, b and c
+a or b
+a
+[]
+1-2.5-true--x
+1 [2,3] {a:4}
+a | b | c
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/join.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_join() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_join.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_join.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid arguments: Cannot join the nested value \["b"\] at index 1
+     --> tests/template/invalid_join\.template:1:4
+      \|
+    1 \| \{% \["a", \["b"\]\] \| join\(", "\) %\}
+      \|    \^-----------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn sort() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/sort.template")
+        .arg("--configuration")
+        .arg("tests/configuration/sort.yml")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"numbers: [-2,1.5,2,3,10] [10,3,2,1.5,-2]
+mixed: [,false,true,1,2,a,b,[1]]
+strings: Eth1 eth1 eth10 eth2
+natural: Eth1 eth1 eth2 eth10
+ignore case: A B a b A a b B Eth1 eth1 eth2 eth10
+versions: 0.1 1.9.2 1.9.10 1.10.0 1.10.0 1.9.10 1.9.2 0.1
+by:
+- api
+- cache
+- db
+- web
+by path:
+- cache
+- db
+- api
+- web
+by path descending:
+- web
+- db
+- api
+- cache
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/sort.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/sort.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_sort() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_sort.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_sort.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid arguments: Unknown sort option 'fast', expected 'natural' or 'ignoreCase'
+     --> tests/template/invalid_sort\.template:1:4
+      \|
+    1 \| \{% \[1, 2\] \| sort\("fast"\) %\}
+      \|    \^-------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn collections() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/collections.template")
+        .arg("--configuration")
+        .arg("tests/configuration/collections.yml")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"pluck: web1, db1, web2, cache1
+pluck path: [ams,ams,fra,]
+where: web1, web2
+where path: web1, db1
+where truthy: web1, web2
+whereNot: db1, cache1
+partition: web1, web2 / web1, web2, cache1
+groupBy:
+web: web1, web2
+db: db1
+cache: cache1
+groupBy path: [ams,fra,]
+countBy: {"web":2,"db":1,"cache":1}
+flatten: [1,2,[3,[4]]] [1,2,3,[4]] [1,[2,[3,[4]]]]
+compact: [1,a,true]
+concat: [1,2,3]
+zip: [[a,1],[b,2]]
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/collections.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/collections.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn lambda() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/lambda.template")
+        .arg("--configuration")
+        .arg("tests/configuration/lambda.yml")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"map: WEB, SSH, APP
+map scope: 10443, 10022, 18080
+map arithmetic: [1,4,9]
+select: app
+filter: web, app
+nested: [[2,3],[4]]
+any: true false
+all: true false
+none: true
+some: true
+truthiness: false true
+- web:443
+- app:8080
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/lambda.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/lambda.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_lambda() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_lambda.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_lambda.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid arguments: Function 'reverse' does not accept a lambda argument
+     --> tests/template/invalid_lambda\.template:1:4
+      \|
+    1 \| \{% \[1, 2\] \| reverse\(x => x\) %\}
+      \|    \^----------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn dictionaries() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/dictionaries.template")
+        .arg("--configuration")
+        .arg("tests/configuration/dictionaries.yml")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"merge: {"port":80,"tls":{"enabled":true},"tags":["web"],"name":"web"}
+deepMerge: {"port":80,"tls":{"enabled":true,"versions":["TLSv1.2"]},"tags":["web"],"name":"web"}
+deepMerge append: {"port":80,"tls":{"enabled":true,"versions":["TLSv1.2"]},"tags":["base","web"],"name":"web"}
+deepMerge merge: {"a":[{"x":1,"y":2},2]}
+pick: {"port":80,"tags":["base"]} {"port":80}
+omit: {"tls":{"enabled":false,"versions":["TLSv1.2"]}}
+get: TLSv1.2 default []
+set: {"enabled":true,"versions":["TLSv1.2"]} {"a":{"b":{"c":1}}} ["core"]
+entries: [{"key":"a","value":1},{"key":"b","value":2}]
+fromEntries: {"a":1,"b":2} true
+mapValues: {"a":10,"b":20}
+mapKeys: {"A":1,"B":2}
+filterKeys: app.kubernetes.io/name, app.kubernetes.io/version
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/dictionaries.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/dictionaries.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_set() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_set.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_set.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid arguments: Cannot set property 'b' of number 1
+     --> tests/template/invalid_set\.template:1:4
+      \|
+    1 \| \{% \{"a": 1\} \| set\("a\.b", 2\) %\}
+      \|    \^----------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn regex_functions() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/regex_functions.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"regexFind: 1.25.3 []
+regexFindAll: [10,20,300] []
+regexCaptures: [eth0.120,eth0,120] ["eth0","eth0",null,null]
+named: {"0":"eth0.120","interface":"eth0","vlan":"120"} 30 []
+regexSplit: [a,b,c,d]
+loop:
+- 81
+- 444
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/regex_functions.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn unicode_strings() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/unicode_strings.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"length: 5 2 1 1
+substring: éllo wörld wörld wör llo
+take: hé hél héllo 👍🏽 [1,2]
+drop: llo lo 👍 [3]
+abbreviate: wör… wörld 👍🏽…
+capitalize: Éa Ünïcode 👍 up
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/unicode_strings.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_substring() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_substring.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_substring.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid arguments: The start of the substring \(4\) cannot be after its end \(2\)
+     --> tests/template/invalid_substring\.template:1:4
+      \|
+    1 \| \{% "héllo" \| substring\(4, 2\) %\}
+      \|    \^-----------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn alignment() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/alignment.template")
+        .arg("--configuration")
+        .arg("tests/configuration/alignment.yml")
+        .assert();
+
+    assert
+        .success()
+        .stdout(r#"|web       |   443|...web....|
+|database  |  5432|.database.|
+|日本      |    80|...日本...|
+-------------------- [] 0000x toolong 007
+indent:
+    server:
+      port: 8080
+
+    debug: true
+nindent:
+  server:
+    port: 8080
+
+  debug: true
+wrap:
+The quick brown fox
+jumps over the lazy
+dog while the cat
+sleeps
+a
+verylongwordthatdoesnotfit
+b
+truncateWords: The quick brown fox… The quick [...] one  two
+"#)
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/alignment.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/alignment.yml'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using YAML format
+$"#).unwrap());
+}
+
+#[test]
+fn repeat_too_long() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/repeat_too_long.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/repeat_too_long.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid arguments: The result would be longer than the maximum of 268435456 bytes
+     --> tests/template/repeat_too_long\.template:1:4
+      \|
+    1 \| \{% "ab" \| repeat\(9223372036854775807\) %\}
+      \|    \^--------------------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn padding_too_long() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/padding_too_long.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/padding_too_long.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Invalid arguments: The result would be longer than the maximum of 268435456 bytes
+     --> tests/template/padding_too_long\.template:1:4
+      \|
+    1 \| \{% "ab" \| padLeft\(9223372036854775807, "xy"\) %\}
+      \|    \^---------------------------------------\^
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_iterable() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_iterable.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_iterable.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Could not iterate: Expected an array, dictionary or null, but got string "abc"
+     --> tests/template/invalid_iterable\.template:1:16
+      \|
+    1 \| \{% for item in "abc" %\}\{% item %\}\{% end %\}
+      \|                \^---\^
+$"#).unwrap());
+}
+
+#[test]
+fn invalid_include_path() {
+    let mut cmd = Command::cargo_bin("template").unwrap();
+    let assert = cmd
+        .arg("--template")
+        .arg("tests/template/invalid_include_path.template")
+        .arg("--configuration")
+        .arg("tests/configuration/empty.json")
+        .assert();
+
+    assert
+        .code(6)
+        .stdout("")
+        .stderr(is_match(r#"^\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using template file 'tests/template/invalid_include_path.template'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Using configuration file 'tests/configuration/empty.json'
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z INFO  template\] Parsing configuration using JSON format
+\[\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z ERROR template\] ERROR: Could not render template: Could not include template: The template path must be a string, but got number 42
+     --> tests/template/invalid_include_path\.template:2:12
+      \|
+    2 \| \{% include 42 %\}
+      \|            \^\^
+$"#).unwrap());
+}